@@ -1,28 +1,65 @@
 use {
-    crate::index::{ContentIndex, PositionIdex, TabIndex, TypedVec},
+    crate::{
+        handle::{ExitMode, Shutdown},
+        index::{ContentIndex, PositionIdex, TabIndex, TypedVec},
+        inputs::{action_events, filter_input, search_input},
+        keymap::{KeyAction, KeyMap},
+        layout::{DEFAULT_WEIGHT, LayoutMode, SavedGroup, SavedLayout},
+        process::{Children, ProcessEvent, ProcessStatus},
+        search::{Filter, FilterEvent, Search, SearchEvent, highlight, match_style},
+    },
     ansi_to_tui::IntoText,
+    crossterm::event::{
+        DisableMouseCapture, EnableMouseCapture, KeyEvent, MouseButton, MouseEvent, MouseEventKind,
+    },
     ratatui::{
-        Frame,
+        Frame, Terminal,
+        backend::Backend,
         layout::{Constraint, Direction as LayoutDirection, Layout, Rect},
-        style::{Style, Stylize},
+        style::{Color, Style, Stylize},
         symbols::{self},
         text::{Line, Text},
         widgets::{
-            Block, Borders, List, ListState, Scrollbar, ScrollbarOrientation, ScrollbarState, Tabs,
+            Block, Borders, Clear, List, ListState, Scrollbar, ScrollbarOrientation,
+            ScrollbarState, Tabs,
         },
     },
     std::{
         cmp::min,
         collections::{BTreeMap, VecDeque},
-        sync::{Arc, LazyLock, Mutex, RwLock, mpsc},
+        io::{self, Write},
+        ops::Range,
+        path::PathBuf,
+        sync::{Arc, Mutex, OnceLock, RwLock, mpsc},
     },
+    tracing::Level,
 };
 
-pub(crate) static MAX_LINES: LazyLock<Mutex<usize>> = LazyLock::new(|| Mutex::new(2_000));
+/// Lines scrolled by a tick of the mouse wheel
+const MOUSE_SCROLL: i32 = 3;
+
+/// Weight added or removed by the resize keys
+const WEIGHT_STEP: u16 = 10;
+
+const MIN_WEIGHT: u16 = 10;
+
+const MAX_WEIGHT: u16 = 1_000;
+
+/// Widths a row keeps its rendered text for, the panes showing it at the same time
+const RENDERED_WIDTHS: usize = 4;
+
+/// Destinations and level of a trace, captured by the `RedirectLayer` for each event
+pub(crate) struct TraceLabel {
+    pub names: Vec<String>,
+    pub level: Level,
+}
 
 pub(crate) enum Direction {
     Left,
     Right,
+    /// Up and down follow the groups as rendered in the last frame
+    Up,
+    Down,
 }
 
 pub(crate) enum DrawEvent {
@@ -30,61 +67,199 @@ pub(crate) enum DrawEvent {
     ChangeSelect(Direction),
     MoveSelect(Direction),
     ChangeTab(Direction),
-    Trace(Vec<u8>),
+    /// Formatted event, sent once the event is fully written
+    Trace(TraceLabel, Vec<u8>),
+    Process(String, ProcessEvent),
+    Search(SearchEvent),
+    Filter(FilterEvent),
+    CycleLevel,
+    CycleLayout,
+    /// Grow or shrink the weight of the selected group
+    ResizeGroup(i16),
+    ToggleZoom,
+    /// Key mapped by the prompt of the selected tab or by the [`KeyMap`]
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    ToggleHelp,
+    Exit,
     Resize,
 }
 
-#[derive(Clone)]
+/// Settings of the draw thread, from the `LogTerminal` builder
+pub(crate) struct DrawOptions {
+    pub mouse: bool,
+    pub keymap: KeyMap,
+    pub exit_mode: ExitMode,
+    pub on_exit: Option<Box<dyn FnOnce() + Send>>,
+    pub shutdown: Arc<Shutdown>,
+    pub children: Children,
+    pub report: PanicReport,
+    /// Rows kept by each tab
+    pub max_lines: usize,
+    /// Tabs shown before their first line
+    pub tabs: Vec<String>,
+    /// Groups of tabs shown before their first line
+    pub groups: Vec<Vec<String>>,
+    pub layout: LayoutMode,
+    /// File the arrangement of the groups is restored from and saved to
+    pub layout_file: Option<PathBuf>,
+}
 
+/// Line of a tab, the clones added to other tabs share the log, its plain and rendered text
+#[derive(Clone)]
 struct Row {
-    log: String,
-    text: Option<Text<'static>>,
-    last_width: u16,
+    log: Arc<str>,
+    /// Text wrapped at the last widths the row was rendered at
+    rendered: Arc<Mutex<Vec<(u16, Text<'static>)>>>,
+    stderr: bool,
+    plain: Arc<OnceLock<String>>,
+    /// `None` for the lines not coming from a trace, they are never hidden by the level
+    level: Option<Level>,
 }
 
 impl Row {
     fn new(log: String) -> Self {
         Self {
-            log,
-            text: None,
-            last_width: 0,
+            log: log.into(),
+            rendered: Default::default(),
+            stderr: false,
+            plain: Default::default(),
+            level: None,
+        }
+    }
+
+    fn with_level(log: String, level: Level) -> Self {
+        Self {
+            level: Some(level),
+            ..Self::new(log)
         }
     }
+
+    fn stderr(log: String) -> Self {
+        Self {
+            stderr: true,
+            ..Self::new(log)
+        }
+    }
+
+    /// The log without the ansi escape codes
+    fn plain(&self) -> &str {
+        self.plain.get_or_init(|| {
+            let Ok(text) = self.log.as_bytes().into_text() else {
+                return self.log.to_string();
+            };
+
+            text.lines
+                .iter()
+                .map(|line| {
+                    line.spans
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
 }
 
 struct Content {
     name: String,
-    lines: RwLock<VecDeque<Row>>,
-    buffer_lines: Vec<Row>,
+    lines: Arc<RwLock<VecDeque<Row>>>,
+    /// Rows received while scrolling, shared with the [`PanicReport`] as `lines`
+    buffer_lines: Arc<Mutex<Vec<Row>>>,
     offset: Offset,
+    status: Option<ProcessStatus>,
+    search: Option<Search>,
+    filter: Option<Filter>,
+    /// Most verbose level shown
+    level: Level,
+    max_lines: usize,
 }
 
 impl Content {
-    pub fn new(name: String, line: String) -> Self {
+    pub fn new(name: String, max_lines: usize) -> Self {
         Self {
             name,
-            lines: RwLock::new(VecDeque::from([Row::new(line)])),
-            buffer_lines: vec![],
+            lines: Default::default(),
+            buffer_lines: Default::default(),
             offset: Offset::new(),
+            status: None,
+            search: None,
+            filter: None,
+            level: Level::TRACE,
+            max_lines,
         }
     }
 
+    /// Name shown in the tab header and in the block title
+    fn title(&self) -> String {
+        match &self.status {
+            Some(status) => format!("{} [{}]", self.name, status.label()),
+            None => self.name.clone(),
+        }
+    }
+
+    fn is_visible(&self, row: &Row) -> bool {
+        row.level.is_none_or(|level| level <= self.level)
+            && self
+                .filter
+                .as_ref()
+                .is_none_or(|filter| filter.is_match(row.plain()))
+    }
+
+    fn cycle_level(&mut self) {
+        self.level = match self.level {
+            Level::TRACE => Level::DEBUG,
+            Level::DEBUG => Level::INFO,
+            Level::INFO => Level::WARN,
+            Level::WARN => Level::ERROR,
+            _ => Level::TRACE,
+        };
+    }
+
+    /// Indexes of the rows not hidden by the filter
+    fn visible_rows(&self) -> Vec<usize> {
+        self.lines
+            .read()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| self.is_visible(row))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
     fn scroll(&mut self, scroll: i32) {
         let was_enabled = self.offset.enabled;
 
+        let visible = self.visible_rows();
+
+        let trace_len = self.lines.read().unwrap().len();
+
+        // scroll over the visible rows only, then map the position back to the row index
+        let mut offset = Offset {
+            offset: visible.partition_point(|row| *row < self.offset.offset(trace_len)),
+            enabled: self.offset.enabled,
+        };
+
         if scroll > 0 {
-            self.offset
-                .scroll_up(scroll, self.lines.read().unwrap().len());
+            offset.scroll_up(scroll, visible.len());
         } else {
-            self.offset
-                .scroll_down(scroll, self.lines.read().unwrap().len());
+            offset.scroll_down(scroll, visible.len());
         }
-        if was_enabled == true && self.offset.enabled == false {
+
+        self.offset = Offset {
+            offset: visible.get(offset.offset).copied().unwrap_or(trace_len),
+            enabled: offset.enabled,
+        };
+
+        if was_enabled && !self.offset.enabled {
             let mut lines = self.lines.write().unwrap();
 
-            lines.extend(self.buffer_lines.drain(..));
+            lines.extend(self.buffer_lines.lock().unwrap().drain(..));
 
-            let to_remove = lines.len().saturating_sub(*MAX_LINES.lock().unwrap());
+            let to_remove = lines.len().saturating_sub(self.max_lines);
 
             if to_remove > 0 {
                 lines.drain(..to_remove);
@@ -96,18 +271,193 @@ impl Content {
         self.offset.offset(self.lines.read().unwrap().len())
     }
 
-    fn add_log(&mut self, log: String) {
+    fn add_log(&mut self, row: Row) {
         if self.offset.enabled {
-            self.buffer_lines.push(Row::new(log));
+            self.buffer_lines.lock().unwrap().push(row);
         } else {
             let mut lines = self.lines.write().unwrap();
-            lines.push_back(Row::new(log));
+            lines.push_back(row);
 
-            if lines.len() > *MAX_LINES.lock().unwrap() {
+            if lines.len() > self.max_lines {
                 lines.pop_front();
             }
         }
     }
+
+    fn on_search(&mut self, event: SearchEvent) {
+        match event {
+            SearchEvent::Start => self.search = Some(Search::new(self.offset())),
+            SearchEvent::Input(char) => {
+                if let Some(search) = &mut self.search {
+                    search.query.push(char);
+                }
+
+                self.search_incremental();
+            },
+            SearchEvent::Backspace => {
+                if let Some(search) = &mut self.search {
+                    search.query.pop();
+                }
+
+                self.search_incremental();
+            },
+            SearchEvent::Confirm => {
+                if let Some(search) = &mut self.search {
+                    if search.query.is_empty() {
+                        self.search = None;
+                    } else {
+                        search.editing = false;
+                    }
+                }
+            },
+            SearchEvent::Next => self.search_step(true),
+            SearchEvent::Previous => self.search_step(false),
+            SearchEvent::Clear => self.search = None,
+        }
+    }
+
+    /// Prompt receiving the keys instead of the bindings
+    fn prompt(&self) -> Option<Prompt> {
+        if self.search.as_ref().is_some_and(|search| search.editing) {
+            Some(Prompt::Search)
+        } else if self.filter.as_ref().is_some_and(|filter| filter.editing) {
+            Some(Prompt::Filter)
+        } else {
+            None
+        }
+    }
+
+    /// Stop editing the prompt as if it was confirmed, the keys go back to the bindings
+    fn close_prompt(&mut self) {
+        match self.prompt() {
+            Some(Prompt::Search) => self.on_search(SearchEvent::Confirm),
+            Some(Prompt::Filter) => self.on_filter(FilterEvent::Confirm),
+            None => {},
+        }
+    }
+
+    fn on_filter(&mut self, event: FilterEvent) {
+        match event {
+            FilterEvent::Start => self.filter = Some(Filter::new()),
+            FilterEvent::Clear => self.filter = None,
+            FilterEvent::Input(char) => self.edit_filter(|filter| filter.input.push(char)),
+            FilterEvent::Backspace => self.edit_filter(|filter| {
+                filter.input.pop();
+            }),
+            FilterEvent::ToggleRegex => self.edit_filter(|filter| filter.regex = !filter.regex),
+            FilterEvent::Confirm => {
+                if let Some(filter) = &mut self.filter {
+                    if filter.input.is_empty() {
+                        self.filter = None;
+                    } else {
+                        filter.editing = false;
+                    }
+                }
+            },
+        }
+    }
+
+    /// Change the open filter and compile it again
+    fn edit_filter(&mut self, edit: impl FnOnce(&mut Filter)) {
+        if let Some(filter) = &mut self.filter {
+            edit(filter);
+            filter.update();
+        }
+    }
+
+    /// Row indexes of the lines matching the current search
+    fn search_matches(&self) -> Vec<usize> {
+        let Some(search) = self
+            .search
+            .as_ref()
+            .filter(|search| !search.query.is_empty())
+        else {
+            return vec![];
+        };
+
+        self.lines
+            .read()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| self.is_visible(row) && row.plain().contains(&search.query))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Jump to the closest match above the position where the search started
+    fn search_incremental(&mut self) {
+        let matches = self.search_matches();
+
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        let target = matches
+            .iter()
+            .rev()
+            .find(|index| **index < search.origin)
+            .or(matches.last())
+            .copied();
+
+        search.current = target;
+
+        if let Some(target) = target {
+            self.offset.jump(target);
+        }
+    }
+
+    fn search_step(&mut self, forward: bool) {
+        let matches = self.search_matches();
+
+        let position = self.offset();
+
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        let from = search.current.unwrap_or(position);
+
+        let target = if forward {
+            matches
+                .iter()
+                .find(|index| **index > from)
+                .or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|index| **index < from)
+                .or(matches.last())
+        }
+        .copied();
+
+        if let Some(target) = target {
+            search.current = Some(target);
+            self.offset.jump(target);
+        }
+    }
+}
+
+enum Prompt {
+    Search,
+    Filter,
+}
+
+/// Where a group was rendered in the last frame, used to dispatch the mouse events
+struct TabArea {
+    tab_index: TabIndex,
+    area: Rect,
+    header: Rect,
+    /// Columns covered by each title of the header
+    titles: Vec<(PositionIdex, Range<u16>)>,
+}
+
+/// Border between two groups being dragged with the mouse
+struct Drag {
+    first: TabIndex,
+    second: TabIndex,
+    direction: LayoutDirection,
 }
 
 #[derive(Default, PartialEq)]
@@ -120,60 +470,277 @@ pub struct State {
     selected_tab: TabPosition,
     open_tabs: BTreeMap<TabIndex, PositionIdex>,
     tabs_position: TypedVec<TabIndex, TypedVec<PositionIdex, ContentIndex>>,
+    /// Share of the screen of each group of `tabs_position`
+    weights: TypedVec<TabIndex, u16>,
     contents: TypedVec<ContentIndex, Content>,
-    trace_names: Arc<Mutex<VecDeque<Option<String>>>>,
     init: bool,
+    areas: Vec<TabArea>,
+    help: bool,
+    keymap: KeyMap,
+    report: PanicReport,
+    max_lines: usize,
+    layout: LayoutMode,
+    drag: Option<Drag>,
+    /// Only the selected content is rendered, across the whole frame
+    zoom: bool,
+    /// Arrangement restored from and saved to `layout_file`
+    saved: SavedLayout,
+    layout_file: Option<PathBuf>,
 }
 
 impl State {
-    pub fn new(trace_names: Arc<Mutex<VecDeque<Option<String>>>>) -> Self {
-        Self {
+    pub fn new(options: &DrawOptions) -> Self {
+        let mut state = Self {
             selected_tab: TabPosition::default(),
             open_tabs: BTreeMap::new(),
             tabs_position: TypedVec::new(),
+            weights: TypedVec::new(),
             contents: TypedVec::new(),
-            trace_names,
             init: false,
+            areas: vec![],
+            help: false,
+            keymap: options.keymap.clone(),
+            report: options.report.clone(),
+            max_lines: options.max_lines,
+            layout: options.layout,
+            drag: None,
+            zoom: false,
+            // an unreadable file is ignored, the tabs are arranged as if it was missing
+            saved: options
+                .layout_file
+                .as_deref()
+                .and_then(|path| SavedLayout::load(path).ok())
+                .unwrap_or_default(),
+            layout_file: options.layout_file.clone(),
+        };
+
+        state.saved.declare(&options.groups);
+
+        for name in options.tabs.iter().chain(options.groups.iter().flatten()) {
+            state.get_or_add_content(name.clone());
         }
+
+        state
     }
 
-    pub fn add_line(&mut self, line: String, name: String) {
-        let Some(tab) = self.contents.iter_mut().find(|tab| tab.name == name) else {
-            let add_selected = !self.init;
+    /// Add the line to every tab of `names`
+    pub fn add_line(&mut self, line: String, names: Vec<String>, level: Level) {
+        let row = Row::with_level(line, level);
 
-            if !self.init {
-                self.init = true;
-            }
+        for name in names {
+            self.get_or_add_content(name).add_log(row.clone());
+        }
+    }
 
-            self.add_content(name, line, add_selected);
+    fn get_or_add_content(&mut self, name: String) -> &mut Content {
+        let content_index = match self.contents.iter().position(|tab| tab.name == name) {
+            Some(index) => ContentIndex(index),
+            None => {
+                let add_selected = !self.init;
 
-            return;
+                if !self.init {
+                    self.init = true;
+                }
+
+                self.add_content(name, add_selected)
+            },
         };
 
-        tab.add_log(line);
+        self.contents.get_mut(content_index).unwrap()
     }
 
-    fn get_selected_tab(&mut self) -> &mut Content {
-        let index = self
-            .tabs_position
-            .get(self.selected_tab.tab_index)
-            .unwrap()
+    /// Content open in the selected group, `None` before the first tab is added
+    fn selected_content(&self) -> Option<ContentIndex> {
+        self.tabs_position
+            .get(self.selected_tab.tab_index)?
             .get(self.selected_tab.position_index)
-            .unwrap();
-
-        self.contents.get_mut(*index).unwrap()
+            .copied()
     }
 
-    pub fn tab_count(&self) -> TabIndex {
-        self.tabs_position.len()
+    fn get_selected_tab(&mut self) -> Option<&mut Content> {
+        let index = self.selected_content()?;
+
+        self.contents.get_mut(index)
     }
 
-    fn add_content(&mut self, name: String, line: String, add_selected: bool) {
-        self.contents.push(Content::new(name, line));
+    fn add_content(&mut self, name: String, add_selected: bool) -> ContentIndex {
+        let content = Content::new(name, self.max_lines);
+
+        self.report.register(&content);
+
+        self.contents.push(content);
 
         let content_index = self.contents.len().manipulate(|index| index - 1);
 
-        self.add_tab(TabIndex(0), content_index, add_selected);
+        self.place_content(content_index, add_selected);
+
+        content_index
+    }
+
+    /// Group and position of the content in the saved layout
+    fn saved_position(&self, content_index: ContentIndex) -> Option<(usize, usize)> {
+        self.saved
+            .find(&self.contents.get(content_index).unwrap().name)
+    }
+
+    /// Group of the saved layout holding the tabs of the group `tab_index`
+    fn saved_group(&self, tab_index: TabIndex) -> Option<usize> {
+        self.tabs_position
+            .get(tab_index)?
+            .iter()
+            .find_map(|content_index| self.saved_position(*content_index))
+            .map(|(group, _)| group)
+    }
+
+    /// Add a new content to the group remembered by the saved layout, to the first group otherwise
+    fn place_content(&mut self, content_index: ContentIndex, add_selected: bool) {
+        let Some((saved_group, saved_position)) = self.saved_position(content_index) else {
+            self.add_tab(TabIndex(0), content_index, add_selected);
+            return;
+        };
+
+        let open = self
+            .saved
+            .is_open(&self.contents.get(content_index).unwrap().name);
+
+        let existing = (0..*self.tabs_position.len())
+            .map(TabIndex)
+            .find(|tab_index| self.saved_group(*tab_index) == Some(saved_group));
+
+        let (tab_index, position_index) = match existing {
+            // join the tabs already shown, in the saved order
+            Some(tab_index) => {
+                let position_index = self
+                    .tabs_position
+                    .get(tab_index)
+                    .unwrap()
+                    .iter()
+                    .position(|content_index| {
+                        self.saved_position(*content_index)
+                            .is_some_and(|(group, position)| {
+                                group == saved_group && position > saved_position
+                            })
+                    })
+                    .unwrap_or(*self.tabs_position.get(tab_index).unwrap().len());
+
+                let position_index = PositionIdex(position_index);
+
+                self.tabs_position
+                    .get_mut(tab_index)
+                    .unwrap()
+                    .insert(*position_index, content_index);
+
+                let open_index = self.open_tabs.get_mut(&tab_index).unwrap();
+
+                if *open_index >= position_index {
+                    *open_index = open_index.manipulate(|i| i + 1);
+                }
+
+                if self.selected_tab.tab_index == tab_index {
+                    if self.selected_tab.position_index >= position_index {
+                        self.selected_tab.position_index =
+                            self.selected_tab.position_index.manipulate(|i| i + 1);
+                    }
+                } else if open {
+                    self.open_tabs.insert(tab_index, position_index);
+                }
+
+                (tab_index, position_index)
+            },
+            // new group before the groups saved after it
+            None => {
+                let tab_index = (0..*self.tabs_position.len())
+                    .map(TabIndex)
+                    .find(|tab_index| {
+                        self.saved_group(*tab_index)
+                            .is_some_and(|group| group > saved_group)
+                    })
+                    .unwrap_or(self.tabs_position.len());
+
+                self.tabs_position
+                    .insert(*tab_index, TypedVec::from(vec![content_index]));
+                self.weights
+                    .insert(*tab_index, self.saved.groups[saved_group].weight);
+
+                self.open_tabs = self
+                    .open_tabs
+                    .iter()
+                    .map(|(k, v)| {
+                        if *k >= tab_index {
+                            (k.manipulate(|i| i + 1), *v)
+                        } else {
+                            (*k, *v)
+                        }
+                    })
+                    .collect();
+
+                self.open_tabs.insert(tab_index, PositionIdex(0));
+
+                if self.selected_tab.tab_index >= tab_index && !add_selected {
+                    self.selected_tab.tab_index = self.selected_tab.tab_index.manipulate(|i| i + 1);
+                }
+
+                (tab_index, PositionIdex(0))
+            },
+        };
+
+        if add_selected {
+            self.select_tab(tab_index, position_index, false);
+        }
+    }
+
+    /// Write the arrangement of the groups to the layout file when it changed
+    pub(crate) fn save_layout(&mut self) {
+        let Some(path) = &self.layout_file else {
+            return;
+        };
+
+        let name =
+            |content_index: &ContentIndex| self.contents.get(*content_index).unwrap().name.clone();
+
+        let mut layout = SavedLayout {
+            groups: self
+                .tabs_position
+                .iter()
+                .enumerate()
+                .map(|(index, tabs)| SavedGroup {
+                    weight: *self.weights.get(TabIndex(index)).unwrap(),
+                    tabs: tabs.iter().map(name).collect(),
+                    open: **self.open_tabs.get(&TabIndex(index)).unwrap(),
+                })
+                .collect(),
+        };
+
+        // keep the tabs without events in this run, next to the tabs saved with them
+        for (group_index, group) in self.saved.groups.iter().enumerate() {
+            let missing = group
+                .tabs
+                .iter()
+                .filter(|tab| layout.find(tab).is_none())
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if missing.is_empty() {
+                continue;
+            }
+
+            match group.tabs.iter().find_map(|tab| layout.find(tab)) {
+                Some((index, _)) => layout.groups[index].tabs.extend(missing),
+                None => layout
+                    .groups
+                    .insert(group_index.min(layout.groups.len()), SavedGroup {
+                        weight: group.weight,
+                        tabs: missing,
+                        open: 0,
+                    }),
+            }
+        }
+
+        if layout != self.saved {
+            // the terminal owns the screen, a failed write can not be reported
+            let _ = layout.save(path);
+            self.saved = layout;
+        }
     }
 
     /// return true if the tab was removed
@@ -190,6 +757,7 @@ impl State {
 
             if tabs.is_empty() {
                 self.tabs_position.remove(tab_index);
+                self.weights.remove(tab_index);
 
                 // remove the tab from the open_tabs
                 self.open_tabs.remove(&tab_index);
@@ -229,6 +797,7 @@ impl State {
             }
 
             self.tabs_position.push(TypedVec::from(vec![content_index]));
+            self.weights.push(DEFAULT_WEIGHT);
 
             PositionIdex(0)
         };
@@ -281,35 +850,341 @@ impl State {
     }
 
     fn on_scroll(&mut self, scroll: i32) -> Action {
-        let tab = self.get_selected_tab();
+        let Some(tab) = self.get_selected_tab() else {
+            return Action::Continue;
+        };
+
         tab.scroll(scroll);
         Action::Draw
     }
 
-    fn on_trace_event(&mut self, trace: Vec<u8>) -> Action {
-        let name = self
-            .trace_names
-            .lock()
-            .unwrap()
-            .pop_front()
-            .expect("trace received but no label detected");
+    fn on_trace_event(&mut self, label: TraceLabel, trace: Vec<u8>) -> Action {
+        let trace = if let Ok(trace) = String::from_utf8(trace) {
+            trace
+        } else {
+            return Action::Continue;
+        };
+
+        self.add_line(trace, label.names, label.level);
+
+        Action::Draw
+    }
+
+    fn on_process_event(&mut self, name: String, event: ProcessEvent) -> Action {
+        let content = self.get_or_add_content(name);
 
-        let Some(name) = name else {
+        match event {
+            ProcessEvent::Started => content.status = Some(ProcessStatus::Running),
+            ProcessEvent::Stdout(line) => content.add_log(Row::new(line)),
+            ProcessEvent::Stderr(line) => content.add_log(Row::stderr(line)),
+            ProcessEvent::Exited(status) => content.status = Some(ProcessStatus::Exited(status)),
+        }
+
+        Action::Draw
+    }
+
+    fn on_search(&mut self, event: SearchEvent) -> Action {
+        let Some(tab) = self.get_selected_tab() else {
             return Action::Continue;
         };
 
-        let trace = if let Ok(trace) = String::from_utf8(trace) {
-            trace
-        } else {
+        tab.on_search(event);
+        Action::Draw
+    }
+
+    fn on_filter(&mut self, event: FilterEvent) -> Action {
+        let Some(tab) = self.get_selected_tab() else {
+            return Action::Continue;
+        };
+
+        tab.on_filter(event);
+        Action::Draw
+    }
+
+    fn on_key(&mut self, key_event: KeyEvent) -> Action {
+        let prompt = self
+            .selected_content()
+            .and_then(|index| self.contents.get(index).unwrap().prompt());
+
+        let events = match prompt {
+            Some(Prompt::Search) => search_input(key_event)
+                .map(DrawEvent::Search)
+                .into_iter()
+                .collect(),
+            Some(Prompt::Filter) => filter_input(key_event)
+                .map(DrawEvent::Filter)
+                .into_iter()
+                .collect(),
+            None => self
+                .keymap
+                .action(&key_event)
+                .map(action_events)
+                .unwrap_or_default(),
+        };
+
+        let mut action = Action::Continue;
+
+        for event in events {
+            if let Action::Draw = handle_draw_event(self, event) {
+                action = Action::Draw;
+            }
+        }
+
+        action
+    }
+
+    fn on_cycle_level(&mut self) -> Action {
+        let Some(tab) = self.get_selected_tab() else {
+            return Action::Continue;
+        };
+
+        tab.cycle_level();
+        Action::Draw
+    }
+
+    fn on_resize_group(&mut self, step: i16) -> Action {
+        let Some(weight) = self.weights.get_mut(self.selected_tab.tab_index) else {
+            return Action::Continue;
+        };
+
+        *weight = weight
+            .saturating_add_signed(step * WEIGHT_STEP as i16)
+            .clamp(MIN_WEIGHT, MAX_WEIGHT);
+
+        Action::Draw
+    }
+
+    /// Border between two adjacent groups under the cursor
+    fn border_at(&self, column: u16, row: u16) -> Option<Drag> {
+        self.areas.iter().find_map(|first| {
+            self.areas.iter().find_map(|second| {
+                let (first_area, second_area) = (first.area, second.area);
+
+                let direction = if first_area.right() == second_area.x
+                    && column + 1 == first_area.right()
+                    && (first_area.y..first_area.bottom()).contains(&row)
+                    && (second_area.y..second_area.bottom()).contains(&row)
+                {
+                    LayoutDirection::Horizontal
+                // the rows of the grid have the same height
+                } else if self.layout != LayoutMode::Grid
+                    && first_area.bottom() == second_area.y
+                    && row + 1 == first_area.bottom()
+                    && (first_area.x..first_area.right()).contains(&column)
+                    && (second_area.x..second_area.right()).contains(&column)
+                {
+                    LayoutDirection::Vertical
+                } else {
+                    return None;
+                };
+
+                Some(Drag {
+                    first: first.tab_index,
+                    second: second.tab_index,
+                    direction,
+                })
+            })
+        })
+    }
+
+    /// Move the dragged border to the cursor, the two groups keep the sum of their weights
+    fn on_drag(&mut self, column: u16, row: u16) -> Action {
+        let Some(drag) = &self.drag else {
+            return Action::Continue;
+        };
+
+        let area = |tab_index| {
+            self.areas
+                .iter()
+                .find(|tab_area| tab_area.tab_index == tab_index)
+                .map(|tab_area| tab_area.area)
+        };
+
+        let (Some(first), Some(second)) = (area(drag.first), area(drag.second)) else {
+            return Action::Continue;
+        };
+
+        let (start, size, total) = match drag.direction {
+            LayoutDirection::Horizontal => (first.x, column + 1, first.width + second.width),
+            LayoutDirection::Vertical => (first.y, row + 1, first.height + second.height),
+        };
+
+        // keep at least the borders and a line of each group
+        let size = size
+            .saturating_sub(start)
+            .clamp(3, total.saturating_sub(3).max(3));
+
+        let (first, second) = (drag.first, drag.second);
+
+        // the stack is as wide as its average group, scale all of it to keep the heights of its rows
+        if self.layout == LayoutMode::MainStack && drag.direction == LayoutDirection::Horizontal {
+            let main = *self.weights.get(first).unwrap() as u32;
+
+            let stack = &mut self.weights[1..];
+
+            let average =
+                stack.iter().map(|weight| *weight as u32).sum::<u32>() / stack.len() as u32;
+
+            let new_average = main * (total - size) as u32 / size as u32;
+
+            for weight in stack {
+                *weight = (*weight as u32 * new_average / average.max(1)).clamp(1, u16::MAX as u32)
+                    as u16;
+            }
+
+            return Action::Draw;
+        }
+
+        let weights = *self.weights.get(first).unwrap() + *self.weights.get(second).unwrap();
+
+        let first_weight = ((weights as u32 * size as u32) / total.max(1) as u32) as u16;
+
+        *self.weights.get_mut(first).unwrap() = first_weight.max(1);
+        *self.weights.get_mut(second).unwrap() = weights.saturating_sub(first_weight).max(1);
+
+        Action::Draw
+    }
+
+    fn on_mouse(&mut self, event: MouseEvent) -> Action {
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.drag = self.border_at(event.column, event.row);
+
+                if self.drag.is_some() {
+                    return Action::Continue;
+                }
+            },
+            MouseEventKind::Drag(MouseButton::Left) => {
+                return self.on_drag(event.column, event.row);
+            },
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.drag = None;
+                return Action::Continue;
+            },
+            _ => {},
+        }
+
+        let Some(tab_area) = self
+            .areas
+            .iter()
+            .find(|tab_area| tab_area.area.contains((event.column, event.row).into()))
+        else {
             return Action::Continue;
         };
 
-        self.add_line(trace, name);
+        let tab_index = tab_area.tab_index;
 
+        match event.kind {
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let scroll = if let MouseEventKind::ScrollUp = event.kind {
+                    MOUSE_SCROLL
+                } else {
+                    -MOUSE_SCROLL
+                };
+
+                let open_tab = *self.open_tabs.get(&tab_index).unwrap();
+                let content_index = *self
+                    .tabs_position
+                    .get(tab_index)
+                    .unwrap()
+                    .get(open_tab)
+                    .unwrap();
+
+                self.contents.get_mut(content_index).unwrap().scroll(scroll);
+            },
+            MouseEventKind::Down(MouseButton::Left) => {
+                let title = tab_area
+                    .titles
+                    .iter()
+                    .find(|(_, columns)| {
+                        event.row == tab_area.header.y && columns.contains(&event.column)
+                    })
+                    .map(|(position_index, _)| *position_index);
+
+                let position_index =
+                    title.unwrap_or_else(|| *self.open_tabs.get(&tab_index).unwrap());
+
+                self.select_tab(
+                    tab_index,
+                    position_index,
+                    tab_index == self.selected_tab.tab_index,
+                );
+            },
+            _ => return Action::Continue,
+        }
+
+        Action::Draw
+    }
+
+    /// Once the terminal is closed, the events are written to stderr
+    pub(crate) fn on_closed_event(&mut self, event: DrawEvent) {
+        match event {
+            DrawEvent::Trace(_, trace) => {
+                let _ = io::stderr().write_all(&trace);
+            },
+            DrawEvent::Process(name, ProcessEvent::Stdout(line) | ProcessEvent::Stderr(line)) => {
+                eprintln!("[{name}] {line}");
+            },
+            DrawEvent::Process(name, ProcessEvent::Exited(status)) => {
+                eprintln!("[{name}] {}", ProcessStatus::Exited(status).label());
+            },
+            _ => {},
+        }
+    }
+
+    fn on_toggle_zoom(&mut self) -> Action {
+        self.zoom = !self.zoom;
+        Action::Draw
+    }
+
+    fn on_cycle_layout(&mut self) -> Action {
+        self.layout = self.layout.next();
+        Action::Draw
+    }
+
+    /// Nearest group rendered in `direction` from the selected one in the last frame
+    fn neighbour_group(&self, direction: Direction) -> Option<TabIndex> {
+        let current = self
+            .areas
+            .iter()
+            .find(|tab_area| tab_area.tab_index == self.selected_tab.tab_index)?
+            .area;
+
+        let center = |area: Rect| (area.x + area.width / 2, area.y + area.height / 2);
+
+        let (x, y) = center(current);
+
+        self.areas
+            .iter()
+            .filter(|tab_area| match direction {
+                Direction::Left => tab_area.area.right() <= current.x,
+                Direction::Right => tab_area.area.x >= current.right(),
+                Direction::Up => tab_area.area.bottom() <= current.y,
+                Direction::Down => tab_area.area.y >= current.bottom(),
+            })
+            .min_by_key(|tab_area| {
+                let (area_x, area_y) = center(tab_area.area);
+
+                // closest on the axis of the direction first, then on the other one
+                match direction {
+                    Direction::Left | Direction::Right => (x.abs_diff(area_x), y.abs_diff(area_y)),
+                    Direction::Up | Direction::Down => (y.abs_diff(area_y), x.abs_diff(area_x)),
+                }
+            })
+            .map(|tab_area| tab_area.tab_index)
+    }
+
+    fn on_toggle_help(&mut self) -> Action {
+        self.help = !self.help;
         Action::Draw
     }
 
     fn on_change_select(&mut self, direction: Direction) -> Action {
+        // nothing to select or move before the first tab
+        if self.selected_content().is_none() {
+            return Action::Continue;
+        }
+
         match direction {
             Direction::Left => {
                 if self.selected_tab.tab_index == TabIndex(0)
@@ -388,10 +1263,17 @@ impl State {
 
                 Action::Draw
             },
+            // the tabs of a group are always in a row
+            Direction::Up | Direction::Down => Action::Continue,
         }
     }
 
     fn on_move_select(&mut self, direction: Direction) -> Action {
+        // nothing to select or move before the first tab
+        if self.selected_content().is_none() {
+            return Action::Continue;
+        }
+
         let current_tab_index = self.selected_tab.tab_index;
 
         match direction {
@@ -428,6 +1310,22 @@ impl State {
                     next_tab = next_tab.manipulate(|index| index - 1);
                 }
 
+                self.add_tab(next_tab, current_content_index, true);
+            },
+            Direction::Up | Direction::Down => {
+                let Some(mut next_tab) = self.neighbour_group(direction) else {
+                    return Action::Continue;
+                };
+
+                let current_content_index = *self.get_current_content_index();
+
+                let removed =
+                    self.remove_tab(current_tab_index, self.selected_tab.position_index, true);
+
+                if removed && next_tab > current_tab_index {
+                    next_tab = next_tab.manipulate(|index| index - 1);
+                }
+
                 self.add_tab(next_tab, current_content_index, true);
             },
         }
@@ -436,6 +1334,11 @@ impl State {
     }
 
     fn on_change_tab(&mut self, direction: Direction) -> Action {
+        // nothing to select or move before the first tab
+        if self.selected_content().is_none() {
+            return Action::Continue;
+        }
+
         let current_tab_index = self.selected_tab.tab_index;
 
         match direction {
@@ -468,6 +1371,18 @@ impl State {
                     position_index: *self.open_tabs.get(&next_tab).unwrap(),
                 };
 
+                Action::Draw
+            },
+            Direction::Up | Direction::Down => {
+                let Some(next_tab) = self.neighbour_group(direction) else {
+                    return Action::Continue;
+                };
+
+                self.selected_tab = TabPosition {
+                    tab_index: next_tab,
+                    position_index: *self.open_tabs.get(&next_tab).unwrap(),
+                };
+
                 Action::Draw
             },
         }
@@ -498,7 +1413,7 @@ impl Offset {
             self.enabled = true;
         }
 
-        self.offset = self.offset.saturating_sub(scroll.abs() as usize);
+        self.offset = self.offset.saturating_sub(scroll.unsigned_abs() as usize);
     }
 
     fn scroll_down(&mut self, scroll: i32, trace_len: usize) {
@@ -506,13 +1421,22 @@ impl Offset {
             return;
         }
 
-        self.offset = min(self.offset.saturating_add(scroll.abs() as usize), trace_len);
+        self.offset = min(
+            self.offset.saturating_add(scroll.unsigned_abs() as usize),
+            trace_len,
+        );
 
         if self.offset == trace_len {
             self.enabled = false;
         }
     }
 
+    /// Start scrolling with `row` as the selected line
+    fn jump(&mut self, row: usize) {
+        self.offset = row;
+        self.enabled = true;
+    }
+
     fn offset(&self, trace_len: usize) -> usize {
         if self.enabled {
             self.offset
@@ -522,37 +1446,178 @@ impl Offset {
     }
 }
 
-pub(crate) fn draw_thread(
-    trace_names: Arc<Mutex<VecDeque<Option<String>>>>,
-    rx: mpsc::Receiver<DrawEvent>,
-) {
+pub(crate) fn draw_thread(rx: mpsc::Receiver<DrawEvent>, mut options: DrawOptions) {
     let mut terminal = ratatui::init();
 
-    let mut state = State::new(trace_names);
+    if options.mouse {
+        crossterm::execute!(io::stdout(), EnableMouseCapture).unwrap();
+    }
+
+    let mut state = State::new(&options);
+
+    let mut closed = false;
 
     loop {
         if let Ok(trace) = rx.recv() {
+            if closed {
+                state.on_closed_event(trace);
+                continue;
+            }
+
+            if let DrawEvent::Exit = trace {
+                state.save_layout();
+                close(&mut options);
+                closed = true;
+                continue;
+            }
+
             let action = handle_draw_event(&mut state, trace);
 
             if let Action::Continue = action {
                 continue;
             }
 
-            let tabs = state.tab_count();
+            draw(&mut terminal, &mut state, &options).unwrap();
+        }
+    }
+}
 
-            terminal
-                .draw(|frame| {
-                    let main_chunk = Layout::default()
-                        .direction(LayoutDirection::Horizontal)
-                        .constraints(vec![Constraint::Ratio(1, *tabs as u32); *tabs])
-                        .split(frame.area());
+/// Render the state on the terminal, whatever its backend
+pub(crate) fn draw<B: Backend>(
+    terminal: &mut Terminal<B>,
+    state: &mut State,
+    options: &DrawOptions,
+) -> io::Result<()> {
+    let mut areas = vec![];
+
+    terminal.draw(|frame| {
+        let frame_chunk = Layout::default()
+            .direction(LayoutDirection::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(frame.area());
+
+        // only the selected content while zoomed, without the header of its group
+        if let Some(content_index) = state.selected_content().filter(|_| state.zoom) {
+            let content = state.contents.get(content_index).unwrap();
+
+            render_content(content, true, frame_chunk[0], frame);
+
+            areas.push(TabArea {
+                tab_index: state.selected_tab.tab_index,
+                area: frame_chunk[0],
+                header: Rect::default(),
+                titles: vec![],
+            });
+        } else {
+            let main_chunk = state.layout.split(frame_chunk[0], &state.weights);
 
-                    for (index, content) in state.tabs_position.iter().enumerate() {
-                        render_tab(TabIndex(index), content, main_chunk[index], &state, frame);
-                    }
-                })
-                .unwrap();
+            for (index, content) in state.tabs_position.iter().enumerate() {
+                let index = TabIndex(index);
+
+                areas.push(render_tab(index, content, main_chunk[*index], state, frame));
+            }
+        }
+
+        render_status(options, state.layout, frame_chunk[1], frame);
+
+        if state.help {
+            render_help(options, frame);
         }
+    })?;
+
+    state.areas = areas;
+
+    Ok(())
+}
+
+pub(crate) fn restore_terminal() {
+    let _ = crossterm::execute!(io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+}
+
+/// Notify the [`crate::TerminalHandle`]s and run the `on_exit` callback
+pub(crate) fn shutdown(options: &mut DrawOptions) {
+    options.shutdown.request();
+
+    if let Some(on_exit) = options.on_exit.take() {
+        on_exit();
+    }
+}
+
+/// Rows of every [`Content`], printed to stderr by the panic hook
+#[derive(Clone, Default)]
+pub(crate) struct PanicReport {
+    tabs: Arc<Mutex<Vec<ReportTab>>>,
+}
+
+struct ReportTab {
+    name: String,
+    lines: Arc<RwLock<VecDeque<Row>>>,
+    buffer_lines: Arc<Mutex<Vec<Row>>>,
+}
+
+impl PanicReport {
+    fn register(&self, content: &Content) {
+        self.tabs.lock().unwrap().push(ReportTab {
+            name: content.name.clone(),
+            lines: content.lines.clone(),
+            buffer_lines: content.buffer_lines.clone(),
+        });
+    }
+
+    /// Write the last `count` rows of every tab to stderr.
+    ///
+    /// The locks are only tried, the panicking thread may be holding one of them.
+    fn print(&self, count: usize) {
+        let Ok(tabs) = self.tabs.try_lock() else {
+            return;
+        };
+
+        let mut stderr = io::stderr().lock();
+
+        for tab in tabs.iter() {
+            let _ = writeln!(stderr, "----- {} -----", tab.name);
+
+            let (Ok(lines), Ok(buffer_lines)) = (tab.lines.try_read(), tab.buffer_lines.try_lock())
+            else {
+                let _ = writeln!(stderr, "<rows locked by the panicking thread>");
+                continue;
+            };
+
+            let rows = lines.iter().chain(buffer_lines.iter()).collect::<Vec<_>>();
+
+            for row in &rows[rows.len().saturating_sub(count)..] {
+                let _ = writeln!(stderr, "{}", row.plain());
+            }
+        }
+    }
+}
+
+/// Restore the terminal before the panic message is printed, then print the last `lines` rows of
+/// every tab so the context of the crash is not lost
+pub(crate) fn set_panic_hook(report: PanicReport, lines: usize) {
+    let hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+
+        hook(info);
+
+        if lines > 0 {
+            report.print(lines);
+        }
+    }));
+}
+
+/// Restore the terminal and run the exit procedure of the [`ExitMode`]
+fn close(options: &mut DrawOptions) {
+    restore_terminal();
+
+    shutdown(options);
+
+    if options.exit_mode == ExitMode::Process {
+        options.children.kill_all();
+        std::process::exit(0);
     }
 }
 
@@ -562,7 +1627,7 @@ fn render_tab(
     area: Rect,
     state: &State,
     frame: &mut Frame,
-) {
+) -> TabArea {
     let mut header = vec![];
 
     let mut selected = None;
@@ -574,7 +1639,7 @@ fn render_tab(
     for (position_index, content_index) in content.iter().enumerate() {
         let position_index = PositionIdex(position_index);
         let c = state.contents.get(*content_index).unwrap();
-        header.push(c.name.clone());
+        header.push(c.title());
 
         if position_index == *render_index {
             to_render = Some(content_index);
@@ -597,6 +1662,16 @@ fn render_tab(
         .constraints([Constraint::Length(1), Constraint::Min(0)])
         .split(area);
 
+    // Tabs are rendered as " title " separated by a single column divider
+    let mut titles = vec![];
+    let mut column = chunk[0].x;
+
+    for (position_index, title) in header.iter().enumerate() {
+        let width = Line::from(title.as_str()).width() as u16 + 2;
+        titles.push((PositionIdex(position_index), column..column + width));
+        column += width + 1;
+    }
+
     let mut tabs = Tabs::new(header).highlight_style(Style::default());
 
     if let Some(selected) = selected {
@@ -608,63 +1683,95 @@ fn render_tab(
     frame.render_widget(tabs, chunk[0]);
 
     render_content(content, selected.is_some(), chunk[1], frame);
+
+    TabArea {
+        tab_index: index,
+        area,
+        header: chunk[0],
+        titles,
+    }
 }
 
 fn render_content(tab: &Content, selected: bool, area: Rect, frame: &mut Frame) {
+    let editing_filter = tab.filter.as_ref().filter(|filter| filter.editing);
+
+    let (area, prompt_area) = if tab.search.is_some() || editing_filter.is_some() {
+        let chunk = Layout::default()
+            .direction(LayoutDirection::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(area);
+
+        (chunk[0], Some(chunk[1]))
+    } else {
+        (area, None)
+    };
+
     let raw_offset = tab.offset();
 
-    let mut lines = tab.lines.write().unwrap();
+    let query = tab
+        .search
+        .as_ref()
+        .map(|search| search.query.as_str())
+        .filter(|query| !query.is_empty());
+
+    let current_match = tab.search.as_ref().and_then(|search| search.current);
+
+    let mut matches = 0;
+
+    let lines = tab.lines.read().unwrap();
 
     let mut trace_len = 0;
 
     let mut offset = 0;
 
     let messages = lines
-        .iter_mut()
+        .iter()
         .enumerate()
         .flat_map(|(i, log)| {
-            if let (Some(text), true) = (&log.text, log.last_width == area.width) {
-                if i < raw_offset {
-                    offset += text.lines.len();
-                }
-
-                trace_len += text.lines.len();
-
-                return text.clone();
+            if !tab.is_visible(log) {
+                return Text::default();
             }
 
-            let lines = textwrap::wrap(&log.log, area.width.saturating_sub(3) as usize)
-                .into_iter()
-                .filter_map(|text| {
-                    if text.is_empty() {
-                        return None;
-                    } else {
-                        text.as_ref().into_text().ok().map(|text| text.lines)
-                    }
-                })
-                .flatten()
-                .collect::<Vec<_>>();
+            let mut text = render_row(log, area.width);
 
             if i < raw_offset {
-                offset += lines.len();
+                offset += text.lines.len();
             }
 
-            trace_len += lines.len();
+            trace_len += text.lines.len();
 
-            let parsed_text = Text::from(lines);
+            if let Some(query) = query.filter(|query| log.plain().contains(query)) {
+                matches += 1;
 
-            log.text = Some(parsed_text.clone());
-            log.last_width = area.width;
+                let style = match_style(current_match == Some(i));
+
+                text.lines = text
+                    .lines
+                    .into_iter()
+                    .map(|line| highlight(line, query, style))
+                    .collect();
+            }
 
-            parsed_text
+            text
         })
         .collect::<Vec<_>>();
 
+    // Render the filter or the search prompt
+    if let Some(prompt_area) = prompt_area {
+        let prompt = match (editing_filter, &tab.search) {
+            (Some(filter), _) => filter.prompt(),
+            (None, Some(search)) => search.prompt(matches),
+            (None, None) => String::new(),
+        };
+
+        frame.render_widget(Line::from(prompt).yellow(), prompt_area);
+    }
+
     // Render the list
     {
         let mut block = Block::default()
             .title(
-                Line::from(format!(" {} ", tab.name))
+                Line::from(format!(" {} ", tab.title()))
                     .gray()
                     .bold()
                     .centered(),
@@ -672,12 +1779,28 @@ fn render_content(tab: &Content, selected: bool, area: Rect, frame: &mut Frame)
             .borders(Borders::ALL)
             .border_set(symbols::border::ROUNDED);
 
+        let mut indicators = vec![];
+
         if offset != trace_len {
-            block = block.title_bottom(
-                Line::from(format!(" Scrolling: {} ", trace_len - offset))
-                    .gray()
-                    .left_aligned(),
+            indicators.push(format!("Scrolling: {}", trace_len - offset));
+        }
+
+        if tab.level != Level::TRACE {
+            indicators.push(format!("Level: {}", tab.level));
+        }
+
+        if let Some(filter) = &tab.filter {
+            indicators.push(filter.label());
+        }
+
+        // a single title, so that the indicators are cut instead of drawn over each other
+        if !indicators.is_empty() {
+            let title = truncate(
+                format!(" {} ", indicators.join(" │ ")),
+                area.width.saturating_sub(2) as usize,
             );
+
+            block = block.title_bottom(Line::from(title).gray().left_aligned());
         }
 
         if selected {
@@ -705,13 +1828,215 @@ fn render_content(tab: &Content, selected: bool, area: Rect, frame: &mut Frame)
     }
 }
 
-fn handle_draw_event(state: &mut State, event: DrawEvent) -> Action {
-    match event {
+/// Cut `text` to `width` characters, ending with an ellipsis when cut
+fn truncate(text: String, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text;
+    }
+
+    let mut text = text
+        .chars()
+        .take(width.saturating_sub(1))
+        .collect::<String>();
+    text.push('…');
+    text
+}
+
+fn render_status(options: &DrawOptions, layout: LayoutMode, area: Rect, frame: &mut Frame) {
+    let mut status = format!(" layout: {} ", layout.label());
+
+    if let Some(key) = options.keymap.keys_of(KeyAction::Help).first() {
+        status = format!(" {key}: help │{status}");
+    }
+
+    frame.render_widget(Line::from(status).gray(), area);
+}
+
+fn render_help(options: &DrawOptions, frame: &mut Frame) {
+    let mut bindings = KeyAction::ALL
+        .iter()
+        .filter_map(|action| {
+            let keys = options.keymap.keys_of(*action);
+
+            if keys.is_empty() {
+                None
+            } else {
+                Some((keys.join(", "), action.description()))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if options.mouse {
+        bindings.push(("Wheel".to_string(), "Scroll the pane under the cursor"));
+        bindings.push(("Click".to_string(), "Focus the pane or select the tab"));
+        bindings.push(("Drag".to_string(), "Resize the groups from their border"));
+    }
+
+    let keys_width = bindings
+        .iter()
+        .map(|(keys, _)| Line::from(keys.as_str()).width())
+        .max()
+        .unwrap_or_default();
+
+    let lines = bindings
+        .into_iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                format!(" {keys:<keys_width$}  ").yellow(),
+                description.into(),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let frame_area = frame.area();
+
+    let width = lines
+        .iter()
+        .map(|line| line.width() as u16 + 3)
+        .max()
+        .unwrap_or_default()
+        .min(frame_area.width);
+
+    let height = (lines.len() as u16 + 2).min(frame_area.height);
+
+    let area = Rect {
+        x: frame_area.x + (frame_area.width - width) / 2,
+        y: frame_area.y + (frame_area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let block = Block::default()
+        .title(Line::from(" Help ").bold().centered())
+        .borders(Borders::ALL)
+        .border_set(symbols::border::ROUNDED)
+        .border_style(Style::default().yellow());
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(List::new(lines).block(block), area);
+}
+
+/// Wrap and parse the row for the given width, the result is cached until the width changes
+fn render_row(log: &Row, width: u16) -> Text<'static> {
+    let mut rendered = log.rendered.lock().unwrap();
+
+    if let Some((_, text)) = rendered
+        .iter()
+        .find(|(rendered_width, _)| *rendered_width == width)
+    {
+        return text.clone();
+    }
+
+    let lines = textwrap::wrap(&log.log, width.saturating_sub(3) as usize)
+        .into_iter()
+        .filter_map(|text| {
+            if text.is_empty() {
+                None
+            } else {
+                text.as_ref().into_text().ok().map(|text| text.lines)
+            }
+        })
+        .flatten()
+        .map(|mut line| {
+            if log.stderr {
+                // keep the colors of the process, paint only the uncolored spans
+                for span in line.spans.iter_mut() {
+                    if span.style.fg.is_none() {
+                        span.style.fg = Some(Color::Red);
+                    }
+                }
+            }
+
+            line
+        })
+        .collect::<Vec<_>>();
+
+    let parsed_text = Text::from(lines);
+
+    if rendered.len() == RENDERED_WIDTHS {
+        rendered.remove(0);
+    }
+
+    rendered.push((width, parsed_text.clone()));
+
+    parsed_text
+}
+
+pub(crate) fn handle_draw_event(state: &mut State, event: DrawEvent) -> Action {
+    // the selection alone is saved on exit, the file is written when the groups change
+    let arranged = match event {
+        DrawEvent::MoveSelect(_) | DrawEvent::ResizeGroup(_) => true,
+        DrawEvent::Mouse(MouseEvent {
+            kind: MouseEventKind::Up(MouseButton::Left),
+            ..
+        }) => state.drag.is_some(),
+        _ => false,
+    };
+
+    let selected = state.selected_content();
+
+    let action = match event {
         DrawEvent::Scroll(scroll) => state.on_scroll(scroll),
-        DrawEvent::Trace(trace) => state.on_trace_event(trace),
+        DrawEvent::Trace(label, trace) => state.on_trace_event(label, trace),
+        DrawEvent::Process(name, event) => state.on_process_event(name, event),
+        DrawEvent::Search(search_event) => state.on_search(search_event),
+        DrawEvent::Filter(filter_event) => state.on_filter(filter_event),
+        DrawEvent::CycleLevel => state.on_cycle_level(),
+        DrawEvent::CycleLayout => state.on_cycle_layout(),
+        // the groups are shown again before moving between them
+        DrawEvent::ChangeSelect(_) | DrawEvent::MoveSelect(_) | DrawEvent::ChangeTab(_)
+            if state.zoom =>
+        {
+            state.on_toggle_zoom()
+        },
+        DrawEvent::ToggleZoom => state.on_toggle_zoom(),
+        DrawEvent::ResizeGroup(step) => state.on_resize_group(step),
+        DrawEvent::Key(key_event) => state.on_key(key_event),
+        DrawEvent::Mouse(mouse_event) => state.on_mouse(mouse_event),
+        DrawEvent::ToggleHelp => state.on_toggle_help(),
         DrawEvent::Resize => Action::Draw,
+        // handled by the draw thread
+        DrawEvent::Exit => Action::Continue,
         DrawEvent::ChangeSelect(select_direction) => state.on_change_select(select_direction),
         DrawEvent::MoveSelect(move_direction) => state.on_move_select(move_direction),
         DrawEvent::ChangeTab(tab_direction) => state.on_change_tab(tab_direction),
+    };
+
+    if arranged {
+        state.save_layout();
+    }
+
+    // a prompt only receives the keys of its own tab
+    if let Some(selected) = selected.filter(|selected| state.selected_content() != Some(*selected))
+    {
+        state.contents.get_mut(selected).unwrap().close_prompt();
+    }
+
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_share_the_rendered_text() {
+        let row = Row::with_level("INFO api: request".to_string(), Level::INFO);
+        let copy = row.clone();
+
+        // the panes of the two tabs have different widths
+        render_row(&row, 40);
+        render_row(&copy, 60);
+
+        assert!(Arc::ptr_eq(&row.rendered, &copy.rendered));
+        assert_eq!(
+            row.rendered
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(width, _)| *width)
+                .collect::<Vec<_>>(),
+            [40, 60]
+        );
     }
 }
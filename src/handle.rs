@@ -0,0 +1,122 @@
+use {
+    crate::{
+        draw::DrawEvent,
+        process::{self, Children},
+    },
+    std::{
+        io,
+        process::Command,
+        sync::{Arc, Condvar, Mutex, mpsc},
+    },
+    tracing_subscriber::{filter::LevelFilter, reload},
+};
+
+/// What happens when the user exits the terminal
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExitMode {
+    /// Restore the terminal, kill the spawned processes and exit the process with code 0
+    #[default]
+    Process,
+    /// Restore the terminal and keep the process running, the logs are written to stderr
+    Stderr,
+}
+
+/// Set once the user exits the terminal
+#[derive(Default)]
+pub(crate) struct Shutdown {
+    requested: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl Shutdown {
+    pub fn request(&self) {
+        *self.requested.lock().unwrap() = true;
+        self.condvar.notify_all();
+    }
+
+    fn is_requested(&self) -> bool {
+        *self.requested.lock().unwrap()
+    }
+
+    fn wait(&self) {
+        let requested = self.requested.lock().unwrap();
+        let _requested = self
+            .condvar
+            .wait_while(requested, |requested| !*requested)
+            .unwrap();
+    }
+}
+
+/// Handle to the terminal, returned by [`crate::LogTerminal::finish`]
+#[derive(Clone)]
+pub struct TerminalHandle {
+    level: Arc<dyn ReloadLevel>,
+    tx: mpsc::Sender<DrawEvent>,
+    shutdown: Arc<Shutdown>,
+    children: Children,
+}
+
+impl TerminalHandle {
+    pub(crate) fn new<S: 'static>(
+        level: reload::Handle<LevelFilter, S>,
+        tx: mpsc::Sender<DrawEvent>,
+        shutdown: Arc<Shutdown>,
+        children: Children,
+    ) -> Self {
+        Self {
+            level: Arc::new(level),
+            tx,
+            shutdown,
+            children,
+        }
+    }
+
+    /// True once the user exited the terminal
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown.is_requested()
+    }
+
+    /// Block until the user exits the terminal.
+    ///
+    /// With [`ExitMode::Process`] the process exits right after the `on_exit` callback, use
+    /// [`ExitMode::Stderr`] to run the cleanup of the application once this returns.
+    pub fn wait_shutdown(&self) {
+        self.shutdown.wait();
+    }
+
+    /// Change the most verbose level shown, the events already recorded are not affected.
+    ///
+    /// Under a scoped dispatcher, such as [`crate::Headless::dispatch`], call it inside the scope:
+    /// `tracing` caches the interest of the callsites again with the default dispatcher of the
+    /// calling thread, which outside the scope disables the callsites for the terminal.
+    pub fn set_max_level(&self, level: impl Into<LevelFilter>) -> Result<(), reload::Error> {
+        self.level.reload(level.into())
+    }
+
+    /// Current most verbose level shown
+    pub fn max_level(&self) -> LevelFilter {
+        self.level.current()
+    }
+
+    /// Same as [`crate::LogTerminal::spawn`], usable after the terminal is started
+    pub fn spawn(&self, name: impl Into<String>, command: Command) -> io::Result<()> {
+        process::spawn(name.into(), command, self.tx.clone(), self.children.clone())
+    }
+}
+
+/// Erase the subscriber type of the [`reload::Handle`]
+trait ReloadLevel: Send + Sync {
+    fn reload(&self, level: LevelFilter) -> Result<(), reload::Error>;
+
+    fn current(&self) -> LevelFilter;
+}
+
+impl<S: 'static> ReloadLevel for reload::Handle<LevelFilter, S> {
+    fn reload(&self, level: LevelFilter) -> Result<(), reload::Error> {
+        reload::Handle::reload(self, level)
+    }
+
+    fn current(&self) -> LevelFilter {
+        self.clone_current().unwrap_or(LevelFilter::OFF)
+    }
+}
@@ -0,0 +1,157 @@
+use {
+    crate::{
+        draw::{DrawEvent, DrawOptions, State, draw, handle_draw_event, set_panic_hook, shutdown},
+        handle::{ExitMode, TerminalHandle},
+        inputs::{EventSource, Inputs},
+    },
+    ratatui::{
+        Terminal,
+        backend::{Backend, TestBackend},
+    },
+    std::{io, sync::mpsc},
+    tracing::Dispatch,
+};
+
+/// Terminal stepped by the caller instead of the draw and inputs threads, built by
+/// [`crate::LogTerminal::finish_headless`] or [`crate::LogTerminal::into_headless_layer`].
+///
+/// The subscriber is not set as global default, the events must be recorded with
+/// [`Headless::dispatch`]:
+///
+/// ```
+/// # use {log_terminal::*, ratatui::backend::TestBackend, std::collections::VecDeque};
+/// let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+///     .finish_headless(TestBackend::new(80, 20), VecDeque::new())
+///     .unwrap();
+///
+/// tracing::dispatcher::with_default(terminal.dispatch(), || tracing::info!("hello"));
+///
+/// terminal.step().unwrap();
+///
+/// assert!(terminal.lines().iter().any(|line| line.contains("hello")));
+/// ```
+pub struct Headless<B: Backend> {
+    terminal: Terminal<B>,
+    state: State,
+    options: DrawOptions,
+    inputs: Inputs,
+    source: Box<dyn EventSource>,
+    rx: mpsc::Receiver<DrawEvent>,
+    dispatch: Dispatch,
+    handle: TerminalHandle,
+    panic_lines: usize,
+    closed: bool,
+}
+
+impl<B: Backend> Headless<B> {
+    pub(crate) fn new(
+        backend: B,
+        options: DrawOptions,
+        source: Box<dyn EventSource>,
+        rx: mpsc::Receiver<DrawEvent>,
+        dispatch: Dispatch,
+        handle: TerminalHandle,
+        panic_lines: usize,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            terminal: Terminal::new(backend)?,
+            state: State::new(&options),
+            inputs: Inputs::new(options.keymap.clone()),
+            options,
+            source,
+            rx,
+            dispatch,
+            handle,
+            panic_lines,
+            closed: false,
+        })
+    }
+
+    /// Dispatcher of the subscriber, to use with [`tracing::dispatcher::with_default`].
+    ///
+    /// It records nothing for a terminal built by [`crate::LogTerminal::into_headless_layer`].
+    pub fn dispatch(&self) -> &Dispatch {
+        &self.dispatch
+    }
+
+    pub fn handle(&self) -> &TerminalHandle {
+        &self.handle
+    }
+
+    /// Process the traces and the outputs of the processes received so far, then the events
+    /// already available from the [`EventSource`], and render the frame
+    pub fn step(&mut self) -> io::Result<()> {
+        while let Ok(event) = self.rx.try_recv() {
+            self.on_event(event);
+        }
+
+        while let Some(event) = self.source.try_next_event() {
+            if let Some(event) = self.inputs.on_event(event) {
+                self.on_event(event);
+            }
+        }
+
+        if !self.closed {
+            draw(&mut self.terminal, &mut self.state, &self.options)?;
+        }
+
+        Ok(())
+    }
+
+    /// True once the exit key was pressed, the following steps write the events to stderr.
+    ///
+    /// The exit follows the [`ExitMode`], except that [`ExitMode::Process`] does not exit the process.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Install the panic hook of the threaded terminal, it restores the terminal and prints the last
+    /// rows of every tab after the panic message, see [`crate::LogTerminal::with_panic_lines`]
+    pub fn set_panic_hook(&self) {
+        set_panic_hook(self.options.report.clone(), self.panic_lines);
+    }
+
+    pub fn backend(&self) -> &B {
+        self.terminal.backend()
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        self.terminal.backend_mut()
+    }
+
+    fn on_event(&mut self, event: DrawEvent) {
+        if self.closed {
+            self.state.on_closed_event(event);
+            return;
+        }
+
+        // as the draw thread, without restoring the terminal nor exiting the process
+        if let DrawEvent::Exit = event {
+            self.state.save_layout();
+            shutdown(&mut self.options);
+
+            if self.options.exit_mode == ExitMode::Process {
+                self.options.children.kill_all();
+            }
+
+            self.closed = true;
+            return;
+        }
+
+        // every step renders, the action is not needed
+        handle_draw_event(&mut self.state, event);
+    }
+}
+
+impl Headless<TestBackend> {
+    /// Rendered rows of the last frame
+    pub fn lines(&self) -> Vec<String> {
+        let buffer = self.backend().buffer();
+
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect()
+    }
+}
@@ -1,98 +1,191 @@
 use {
-    crate::draw::{Direction, DrawEvent},
+    crate::{
+        draw::{Direction, DrawEvent},
+        keymap::{KeyAction, KeyMap},
+        search::{FilterEvent, SearchEvent},
+    },
     crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
-    std::sync::mpsc,
+    std::{collections::VecDeque, sync::mpsc, thread, time::Duration},
 };
 
-macro_rules! keys {
-    ($($name:ident: $value:expr $(=> $modifiers:expr)?),* $(,)?) => {
-        $(
-            const $name: KeyEvent = KeyEvent::new(
-                $value,
-                keys!(@mod $($modifiers)?)
-            );
-        )*
-    };
+/// Pause before reading the terminal again after a failed read
+const READ_RETRY: Duration = Duration::from_millis(100);
 
-    (@mod $modifiers:expr) => {
-        $modifiers
-    };
+/// Source of the terminal events read by the terminal
+pub trait EventSource: Send {
+    /// Next event, blocking until it is available, `None` when there are no more events.
+    ///
+    /// The inputs thread stops at the first `None`.
+    fn next_event(&mut self) -> Option<Event>;
 
+    /// Next event if already available, read by [`crate::Headless::step`] until the first `None`.
+    ///
+    /// Defaults to [`EventSource::next_event`], sources that can block must override it.
+    fn try_next_event(&mut self) -> Option<Event> {
+        self.next_event()
+    }
+}
+
+/// Events of the real terminal, blocking until the next one is available
+pub struct CrosstermEvents;
+
+impl EventSource for CrosstermEvents {
+    fn next_event(&mut self) -> Option<Event> {
+        // a failed read does not end the inputs, the terminal is read again
+        loop {
+            match crossterm::event::read() {
+                Ok(event) => return Some(event),
+                Err(_) => thread::sleep(READ_RETRY),
+            }
+        }
+    }
+
+    fn try_next_event(&mut self) -> Option<Event> {
+        match crossterm::event::poll(Duration::ZERO) {
+            Ok(true) => crossterm::event::read().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl EventSource for mpsc::Receiver<Event> {
+    fn next_event(&mut self) -> Option<Event> {
+        self.recv().ok()
+    }
+
+    fn try_next_event(&mut self) -> Option<Event> {
+        self.try_recv().ok()
+    }
+}
+
+impl EventSource for VecDeque<Event> {
+    fn next_event(&mut self) -> Option<Event> {
+        self.pop_front()
+    }
+}
+
+pub fn inputs_thread(
+    tx: mpsc::Sender<DrawEvent>,
+    keymap: KeyMap,
+    mut source: Box<dyn EventSource>,
+) {
+    let inputs = Inputs::new(keymap);
 
-    (@mod) => {
-        KeyModifiers::NONE
-    };
+    while let Some(event) = source.next_event() {
+        let Some(event) = inputs.on_event(event) else {
+            continue;
+        };
+
+        // the terminal is closed by the draw thread, stop reading the inputs
+        let exit = matches!(event, DrawEvent::Exit);
+
+        if tx.send(event).is_err() || exit {
+            return;
+        }
+    }
 }
 
-keys! {
-    EXIT:            KeyCode::Char('c') => KeyModifiers::CONTROL,
-    // Left
-    SELECT_LEFT:     KeyCode::Left,
-    MOVE_LEFT:       KeyCode::Left      => KeyModifiers::SHIFT,
-    CHANGE_LEFT:     KeyCode::Char('b') => KeyModifiers::ALT,
-    // Right
-    SELECT_RIGHT:    KeyCode::Right,
-    MOVE_RIGHT:      KeyCode::Right     => KeyModifiers::SHIFT,
-    CHANGE_RIGHT:    KeyCode::Char('f') => KeyModifiers::ALT,
-    // Scroll up
-    SCROLL_UP:       KeyCode::Up,
-    SCROLL_10_UP:    KeyCode::Up        => KeyModifiers::SHIFT,
-    SCROLL_ALL_UP:   KeyCode::Up        => KeyModifiers::ALT,
-    // Scroll down
-    SCROLL_DOWN:     KeyCode::Down,
-    SCROLL_10_DOWN:  KeyCode::Down      => KeyModifiers::SHIFT,
-    SCROLL_ALL_DOWN: KeyCode::Down      => KeyModifiers::ALT,
+/// Map the terminal events to [`DrawEvent`]s.
+///
+/// The keys are mapped by the draw state, which knows whether a prompt is open, only the exit is
+/// caught here to stop reading the inputs.
+pub(crate) struct Inputs {
+    keymap: KeyMap,
 }
 
-pub fn inputs_thread(tx: mpsc::Sender<DrawEvent>) {
-    loop {
-        let event = crossterm::event::read().expect("Failed to read event");
+impl Inputs {
+    pub fn new(keymap: KeyMap) -> Self {
+        Self { keymap }
+    }
 
+    pub fn on_event(&self, event: Event) -> Option<DrawEvent> {
         match event {
             Event::Key(key_event) => {
-                // Exit
-                if key_event == EXIT {
-                    ratatui::restore();
-                    std::process::exit(0);
-                }
-                // Left
-                else if key_event == SELECT_LEFT {
-                    tx.send(DrawEvent::ChangeSelect(Direction::Left)).unwrap();
-                } else if key_event == MOVE_LEFT {
-                    tx.send(DrawEvent::MoveSelect(Direction::Left)).unwrap();
-                }  else if key_event == CHANGE_LEFT {
-                    tx.send(DrawEvent::ChangeTab(Direction::Left)).unwrap();
-                }
-                // Right
-                else if key_event == SELECT_RIGHT {
-                    tx.send(DrawEvent::ChangeSelect(Direction::Right)).unwrap();
-                } else if key_event == MOVE_RIGHT {
-                    tx.send(DrawEvent::MoveSelect(Direction::Right)).unwrap();
-                } else if key_event == CHANGE_RIGHT {
-                    tx.send(DrawEvent::ChangeTab(Direction::Right)).unwrap();
-                }
-                // Scroll up
-                else if key_event == SCROLL_UP {
-                    tx.send(DrawEvent::Scroll(1)).unwrap();
-                } else if key_event == SCROLL_10_UP {
-                    tx.send(DrawEvent::Scroll(10)).unwrap();
-                } else if key_event == SCROLL_ALL_UP {
-                    tx.send(DrawEvent::Scroll(i32::MAX)).unwrap();
-                }
-                // Scroll down
-                else if key_event == SCROLL_DOWN {
-                    tx.send(DrawEvent::Scroll(-1)).unwrap();
-                } else if key_event == SCROLL_10_DOWN {
-                    tx.send(DrawEvent::Scroll(-10)).unwrap();
-                } else if key_event == SCROLL_ALL_UP {
-                    tx.send(DrawEvent::Scroll(i32::MAX)).unwrap();
-                } else if key_event == SCROLL_ALL_DOWN {
-                    tx.send(DrawEvent::Scroll(-i32::MAX)).unwrap();
+                if self.keymap.action(&key_event) == Some(KeyAction::Exit) {
+                    Some(DrawEvent::Exit)
+                } else {
+                    Some(DrawEvent::Key(key_event))
                 }
             },
-
-            Event::Resize(_, _) => tx.send(DrawEvent::Resize).unwrap(),
-            _ => {},
+            Event::Mouse(mouse_event) => Some(DrawEvent::Mouse(mouse_event)),
+            Event::Resize(..) => Some(DrawEvent::Resize),
+            _ => None,
         }
     }
 }
+
+pub(crate) fn action_events(action: KeyAction) -> Vec<DrawEvent> {
+    match action {
+        KeyAction::Exit => vec![DrawEvent::Exit],
+        // Left
+        KeyAction::SelectLeft => vec![DrawEvent::ChangeSelect(Direction::Left)],
+        KeyAction::MoveLeft => vec![DrawEvent::MoveSelect(Direction::Left)],
+        KeyAction::ChangeLeft => vec![DrawEvent::ChangeTab(Direction::Left)],
+        // Right
+        KeyAction::SelectRight => vec![DrawEvent::ChangeSelect(Direction::Right)],
+        KeyAction::MoveRight => vec![DrawEvent::MoveSelect(Direction::Right)],
+        KeyAction::ChangeRight => vec![DrawEvent::ChangeTab(Direction::Right)],
+        // Up
+        KeyAction::MoveUp => vec![DrawEvent::MoveSelect(Direction::Up)],
+        KeyAction::ChangeUp => vec![DrawEvent::ChangeTab(Direction::Up)],
+        // Down
+        KeyAction::MoveDown => vec![DrawEvent::MoveSelect(Direction::Down)],
+        KeyAction::ChangeDown => vec![DrawEvent::ChangeTab(Direction::Down)],
+        // Scroll up
+        KeyAction::ScrollUp => vec![DrawEvent::Scroll(1)],
+        KeyAction::Scroll10Up => vec![DrawEvent::Scroll(10)],
+        KeyAction::ScrollAllUp => vec![DrawEvent::Scroll(i32::MAX)],
+        // Scroll down
+        KeyAction::ScrollDown => vec![DrawEvent::Scroll(-1)],
+        KeyAction::Scroll10Down => vec![DrawEvent::Scroll(-10)],
+        KeyAction::ScrollAllDown => vec![DrawEvent::Scroll(-i32::MAX)],
+        // Search
+        KeyAction::Search => vec![DrawEvent::Search(SearchEvent::Start)],
+        KeyAction::SearchNext => vec![DrawEvent::Search(SearchEvent::Next)],
+        KeyAction::SearchPrevious => vec![DrawEvent::Search(SearchEvent::Previous)],
+        // Filter
+        KeyAction::Filter => vec![DrawEvent::Filter(FilterEvent::Start)],
+        // Clear
+        KeyAction::Clear => vec![
+            DrawEvent::Search(SearchEvent::Clear),
+            DrawEvent::Filter(FilterEvent::Clear),
+        ],
+        // Level
+        KeyAction::CycleLevel => vec![DrawEvent::CycleLevel],
+        // Layout
+        KeyAction::CycleLayout => vec![DrawEvent::CycleLayout],
+        KeyAction::Grow => vec![DrawEvent::ResizeGroup(1)],
+        KeyAction::Shrink => vec![DrawEvent::ResizeGroup(-1)],
+        // Zoom
+        KeyAction::Zoom => vec![DrawEvent::ToggleZoom],
+        // Help
+        KeyAction::Help => vec![DrawEvent::ToggleHelp],
+    }
+}
+
+/// Map a key of the search prompt, Enter and Esc close it
+pub(crate) fn search_input(key_event: KeyEvent) -> Option<SearchEvent> {
+    match key_event.code {
+        KeyCode::Enter => Some(SearchEvent::Confirm),
+        KeyCode::Esc => Some(SearchEvent::Clear),
+        KeyCode::Backspace => Some(SearchEvent::Backspace),
+        KeyCode::Char(char) if (key_event.modifiers - KeyModifiers::SHIFT).is_empty() => {
+            Some(SearchEvent::Input(char))
+        },
+        _ => None,
+    }
+}
+
+/// Map a key of the filter prompt, Enter and Esc close it
+pub(crate) fn filter_input(key_event: KeyEvent) -> Option<FilterEvent> {
+    match key_event.code {
+        KeyCode::Enter => Some(FilterEvent::Confirm),
+        KeyCode::Esc => Some(FilterEvent::Clear),
+        KeyCode::Backspace => Some(FilterEvent::Backspace),
+        KeyCode::Tab => Some(FilterEvent::ToggleRegex),
+        KeyCode::Char(char) if (key_event.modifiers - KeyModifiers::SHIFT).is_empty() => {
+            Some(FilterEvent::Input(char))
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,274 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+macro_rules! keys {
+    ($($action:ident: $value:expr $(=> $modifiers:expr)?),* $(,)?) => {
+        vec![
+            $(
+                (
+                    KeyEvent::new(
+                        $value,
+                        keys!(@mod $($modifiers)?)
+                    ),
+                    KeyAction::$action,
+                ),
+            )*
+        ]
+    };
+
+    (@mod $modifiers:expr) => {
+        $modifiers
+    };
+
+    (@mod) => {
+        KeyModifiers::NONE
+    };
+}
+
+/// Action triggered by a key binding of the [`KeyMap`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyAction {
+    Exit,
+    /// Select the tab on the left
+    SelectLeft,
+    /// Move the selected tab in the group on the left
+    MoveLeft,
+    /// Select the group on the left
+    ChangeLeft,
+    /// Select the tab on the right
+    SelectRight,
+    /// Move the selected tab in the group on the right
+    MoveRight,
+    /// Select the group on the right
+    ChangeRight,
+    /// Move the selected tab in the group above
+    MoveUp,
+    /// Select the group above
+    ChangeUp,
+    /// Move the selected tab in the group below
+    MoveDown,
+    /// Select the group below
+    ChangeDown,
+    ScrollUp,
+    Scroll10Up,
+    ScrollAllUp,
+    ScrollDown,
+    Scroll10Down,
+    ScrollAllDown,
+    /// Open the search prompt.
+    ///
+    /// The matches are highlighted line by line after the wrapping, a match split by the wrap is
+    /// counted and reached by the navigation but not highlighted.
+    Search,
+    SearchNext,
+    SearchPrevious,
+    /// Open the filter prompt
+    Filter,
+    /// Clear the search and the filter of the selected tab
+    Clear,
+    /// Cycle the most verbose level shown in the selected tab
+    CycleLevel,
+    /// Switch to the next [`crate::LayoutMode`]
+    CycleLayout,
+    /// Grow the selected group
+    Grow,
+    /// Shrink the selected group
+    Shrink,
+    /// Show only the selected tab, or restore the groups
+    Zoom,
+    /// Show or hide the help
+    Help,
+}
+
+impl KeyAction {
+    /// Every action, in the order they are listed in the help
+    pub const ALL: &[KeyAction] = &[
+        KeyAction::SelectLeft,
+        KeyAction::SelectRight,
+        KeyAction::ChangeLeft,
+        KeyAction::ChangeRight,
+        KeyAction::ChangeUp,
+        KeyAction::ChangeDown,
+        KeyAction::MoveLeft,
+        KeyAction::MoveRight,
+        KeyAction::MoveUp,
+        KeyAction::MoveDown,
+        KeyAction::ScrollUp,
+        KeyAction::ScrollDown,
+        KeyAction::Scroll10Up,
+        KeyAction::Scroll10Down,
+        KeyAction::ScrollAllUp,
+        KeyAction::ScrollAllDown,
+        KeyAction::Search,
+        KeyAction::SearchNext,
+        KeyAction::SearchPrevious,
+        KeyAction::Filter,
+        KeyAction::Clear,
+        KeyAction::CycleLevel,
+        KeyAction::CycleLayout,
+        KeyAction::Grow,
+        KeyAction::Shrink,
+        KeyAction::Zoom,
+        KeyAction::Help,
+        KeyAction::Exit,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            KeyAction::Exit => "Exit",
+            KeyAction::SelectLeft => "Select the previous tab",
+            KeyAction::MoveLeft => "Move the tab in the group on the left",
+            KeyAction::ChangeLeft => "Focus the group on the left",
+            KeyAction::SelectRight => "Select the next tab",
+            KeyAction::MoveRight => "Move the tab in the group on the right",
+            KeyAction::ChangeRight => "Focus the group on the right",
+            KeyAction::MoveUp => "Move the tab in the group above",
+            KeyAction::ChangeUp => "Focus the group above",
+            KeyAction::MoveDown => "Move the tab in the group below",
+            KeyAction::ChangeDown => "Focus the group below",
+            KeyAction::ScrollUp => "Scroll up",
+            KeyAction::Scroll10Up => "Scroll up by 10 lines",
+            KeyAction::ScrollAllUp => "Scroll to the top",
+            KeyAction::ScrollDown => "Scroll down",
+            KeyAction::Scroll10Down => "Scroll down by 10 lines",
+            KeyAction::ScrollAllDown => "Scroll to the bottom",
+            KeyAction::Search => "Search",
+            KeyAction::SearchNext => "Next match",
+            KeyAction::SearchPrevious => "Previous match",
+            KeyAction::Filter => "Filter the lines (tab in the prompt toggles regex)",
+            KeyAction::Clear => "Clear search and filter",
+            KeyAction::CycleLevel => "Cycle the level shown in the tab",
+            KeyAction::CycleLayout => "Switch the layout of the groups",
+            KeyAction::Grow => "Grow the group",
+            KeyAction::Shrink => "Shrink the group",
+            KeyAction::Zoom => {
+                "Show the tab full screen, or restore the groups, also restored by moving between them"
+            },
+            KeyAction::Help => "Show or hide this help",
+        }
+    }
+}
+
+/// Bindings between the [`KeyEvent`]s and the [`KeyAction`]s.
+///
+/// A key triggers a single action, an action can be bound to several keys.
+#[derive(Clone)]
+pub struct KeyMap {
+    bindings: Vec<(KeyEvent, KeyAction)>,
+}
+
+impl KeyMap {
+    /// Key map without any binding
+    pub fn empty() -> Self {
+        Self { bindings: vec![] }
+    }
+
+    /// Bind `key` to `action`, replacing the previous binding of `key`
+    pub fn bind(mut self, key: KeyEvent, action: KeyAction) -> Self {
+        self.bindings.retain(|(bound, _)| !same_key(bound, &key));
+        self.bindings.push((key, action));
+        self
+    }
+
+    /// Remove the binding of `key`
+    pub fn unbind(mut self, key: KeyEvent) -> Self {
+        self.bindings.retain(|(bound, _)| !same_key(bound, &key));
+        self
+    }
+
+    /// Remove every binding of `action`
+    pub fn unbind_action(mut self, action: KeyAction) -> Self {
+        self.bindings.retain(|(_, bound)| *bound != action);
+        self
+    }
+
+    pub fn action(&self, key: &KeyEvent) -> Option<KeyAction> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| same_key(bound, key))
+            .map(|(_, action)| *action)
+    }
+
+    pub fn bindings(&self) -> impl Iterator<Item = (&KeyEvent, KeyAction)> {
+        self.bindings.iter().map(|(key, action)| (key, *action))
+    }
+
+    /// Keys bound to `action`, formatted as `Shift+Left`
+    pub fn keys_of(&self, action: KeyAction) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(key, _)| {
+                if key.modifiers.is_empty() {
+                    key.code.to_string()
+                } else {
+                    format!("{}+{}", key.modifiers, key.code)
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            bindings: keys! {
+                Exit:           KeyCode::Char('c') => KeyModifiers::CONTROL,
+                // Left
+                SelectLeft:     KeyCode::Left,
+                MoveLeft:       KeyCode::Left      => KeyModifiers::SHIFT,
+                ChangeLeft:     KeyCode::Char('b') => KeyModifiers::ALT,
+                // Right
+                SelectRight:    KeyCode::Right,
+                MoveRight:      KeyCode::Right     => KeyModifiers::SHIFT,
+                ChangeRight:    KeyCode::Char('f') => KeyModifiers::ALT,
+                // Up
+                MoveUp:         KeyCode::Up        => KeyModifiers::CONTROL,
+                ChangeUp:       KeyCode::Char('p') => KeyModifiers::ALT,
+                // Down
+                MoveDown:       KeyCode::Down      => KeyModifiers::CONTROL,
+                ChangeDown:     KeyCode::Char('n') => KeyModifiers::ALT,
+                // Scroll up
+                ScrollUp:       KeyCode::Up,
+                Scroll10Up:     KeyCode::Up        => KeyModifiers::SHIFT,
+                ScrollAllUp:    KeyCode::Up        => KeyModifiers::ALT,
+                // Scroll down
+                ScrollDown:     KeyCode::Down,
+                Scroll10Down:   KeyCode::Down      => KeyModifiers::SHIFT,
+                ScrollAllDown:  KeyCode::Down      => KeyModifiers::ALT,
+                // Search
+                Search:         KeyCode::Char('/'),
+                SearchNext:     KeyCode::Char('n'),
+                SearchPrevious: KeyCode::Char('N'),
+                // Filter
+                Filter:         KeyCode::Char('&'),
+                // Clear search and filter
+                Clear:          KeyCode::Esc,
+                // Level shown in the selected tab
+                CycleLevel:     KeyCode::Char('l'),
+                // Layout of the groups
+                CycleLayout:    KeyCode::Char('L'),
+                // Size of the selected group
+                Grow:           KeyCode::Char('+'),
+                Shrink:         KeyCode::Char('-'),
+                // Selected tab full screen
+                Zoom:           KeyCode::Char('z'),
+                // Help
+                Help:           KeyCode::Char('?'),
+            },
+        }
+    }
+}
+
+/// Symbols are reported with or without SHIFT depending on the terminal, ignore it for them
+fn same_key(bound: &KeyEvent, key: &KeyEvent) -> bool {
+    match (bound.code, key.code) {
+        (KeyCode::Char(bound_char), KeyCode::Char(char))
+            if bound_char == char && !char.is_alphabetic() =>
+        {
+            bound.modifiers - KeyModifiers::SHIFT == key.modifiers - KeyModifiers::SHIFT
+                && bound.kind == key.kind
+        },
+        _ => bound == key,
+    }
+}
@@ -0,0 +1,231 @@
+use {
+    ratatui::layout::{Constraint, Direction, Layout, Rect},
+    std::{fmt, fs, io, path::Path},
+};
+
+/// Weight of a new group, the groups share the screen in proportion to their weights
+pub(crate) const DEFAULT_WEIGHT: u16 = 100;
+
+/// Arrangement of the tab groups on the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LayoutMode {
+    /// Side by side columns
+    #[default]
+    Columns,
+    /// Rows stacked from the top
+    Rows,
+    /// Grid with as many rows as columns, the last row may have fewer groups
+    Grid,
+    /// The first group on the left half, the others stacked on the right
+    MainStack,
+}
+
+impl LayoutMode {
+    /// Mode selected by [`crate::KeyAction::CycleLayout`] after this one
+    pub fn next(self) -> Self {
+        match self {
+            LayoutMode::Columns => LayoutMode::Rows,
+            LayoutMode::Rows => LayoutMode::Grid,
+            LayoutMode::Grid => LayoutMode::MainStack,
+            LayoutMode::MainStack => LayoutMode::Columns,
+        }
+    }
+
+    /// Name shown in the status line
+    pub fn label(self) -> &'static str {
+        match self {
+            LayoutMode::Columns => "columns",
+            LayoutMode::Rows => "rows",
+            LayoutMode::Grid => "grid",
+            LayoutMode::MainStack => "main + stack",
+        }
+    }
+
+    /// Area of each group, sized by its weight.
+    ///
+    /// In the grid the weights size the groups of the same row, the rows have the same height.
+    pub(crate) fn split(self, area: Rect, weights: &[u16]) -> Vec<Rect> {
+        match self {
+            LayoutMode::Columns => split(area, Direction::Horizontal, weights),
+            LayoutMode::Rows => split(area, Direction::Vertical, weights),
+            LayoutMode::Grid => {
+                let count = weights.len();
+
+                let columns = (1..=count)
+                    .find(|columns| columns * columns >= count)
+                    .unwrap_or(1);
+
+                let rows = weights.chunks(columns).collect::<Vec<_>>();
+
+                split(area, Direction::Vertical, &vec![1; rows.len()])
+                    .into_iter()
+                    .zip(rows)
+                    .flat_map(|(area, weights)| split(area, Direction::Horizontal, weights))
+                    .collect()
+            },
+            LayoutMode::MainStack if weights.len() > 1 => {
+                let (main, stack) = weights.split_first().unwrap();
+
+                // the stack is as wide as its average group
+                let stack_weight =
+                    stack.iter().map(|weight| *weight as u32).sum::<u32>() / stack.len() as u32;
+
+                let halves = split(area, Direction::Horizontal, &[*main, stack_weight as u16]);
+
+                let mut areas = vec![halves[0]];
+                areas.extend(split(halves[1], Direction::Vertical, stack));
+                areas
+            },
+            LayoutMode::MainStack => split(area, Direction::Horizontal, weights),
+        }
+    }
+}
+
+/// Split `area` in parts proportional to `weights`
+fn split(area: Rect, direction: Direction, weights: &[u16]) -> Vec<Rect> {
+    let total = weights.iter().map(|weight| *weight as u32).sum::<u32>();
+
+    Layout::default()
+        .direction(direction)
+        .constraints(
+            weights
+                .iter()
+                .map(|weight| Constraint::Ratio(*weight as u32, total)),
+        )
+        .split(area)
+        .to_vec()
+}
+
+/// Groups arranged by the user, saved to the file of [`crate::LogTerminal::with_layout_file`].
+///
+/// Each group is a `group <weight>` line followed by a line for each tab name, the open tab is
+/// marked with `*`:
+///
+/// ```text
+/// group 100
+/// * api
+///   worker
+/// group 120
+/// * db
+/// ```
+#[derive(Default, PartialEq)]
+pub(crate) struct SavedLayout {
+    pub groups: Vec<SavedGroup>,
+}
+
+#[derive(PartialEq)]
+pub(crate) struct SavedGroup {
+    pub weight: u16,
+    pub tabs: Vec<String>,
+    /// Position of the open tab in `tabs`
+    pub open: usize,
+}
+
+impl SavedLayout {
+    /// Layout saved in `path`, empty when the file is missing
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Unknown lines are skipped, so an edited file never prevents the start
+    fn parse(text: &str) -> Self {
+        let mut groups = vec![];
+
+        for line in text.lines() {
+            if let Some(weight) = line.strip_prefix("group ") {
+                groups.push(SavedGroup {
+                    weight: weight.trim().parse().unwrap_or(DEFAULT_WEIGHT).max(1),
+                    tabs: vec![],
+                    open: 0,
+                });
+            } else if let (Some(group), Some((mark, name))) =
+                (groups.last_mut(), line.split_at_checked(2))
+            {
+                match mark {
+                    "* " => group.open = group.tabs.len(),
+                    "  " => {},
+                    _ => continue,
+                }
+
+                group.tabs.push(name.to_string());
+            }
+        }
+
+        groups.retain(|group| !group.tabs.is_empty());
+
+        Self { groups }
+    }
+
+    /// Add the groups declared with [`crate::LogTerminal::with_layout`], the tabs already saved
+    /// keep their group
+    pub fn declare(&mut self, groups: &[Vec<String>]) {
+        for tabs in groups {
+            let missing = tabs
+                .iter()
+                .filter(|tab| self.find(tab).is_none())
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if missing.is_empty() {
+                continue;
+            }
+
+            match tabs.iter().find_map(|tab| self.find(tab)) {
+                Some((index, _)) => self.groups[index].tabs.extend(missing),
+                None => self.groups.push(SavedGroup {
+                    weight: DEFAULT_WEIGHT,
+                    tabs: missing,
+                    open: 0,
+                }),
+            }
+        }
+    }
+
+    /// Group and position of the tab `name`
+    pub fn find(&self, name: &str) -> Option<(usize, usize)> {
+        self.groups
+            .iter()
+            .enumerate()
+            .find_map(|(group_index, group)| {
+                group
+                    .tabs
+                    .iter()
+                    .position(|tab| tab == name)
+                    .map(|position| (group_index, position))
+            })
+    }
+
+    /// True if `name` is the open tab of its group
+    pub fn is_open(&self, name: &str) -> bool {
+        self.find(name)
+            .is_some_and(|(group, position)| self.groups[group].open == position)
+    }
+}
+
+impl fmt::Display for SavedLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for group in &self.groups {
+            writeln!(f, "group {}", group.weight)?;
+
+            for (position, tab) in group.tabs.iter().enumerate() {
+                let mark = if position == group.open {
+                    '*'
+                } else {
+                    ' '
+                };
+
+                writeln!(f, "{mark} {tab}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
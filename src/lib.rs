@@ -1,6 +1,23 @@
 mod draw;
-mod log;
-mod inputs;
+mod handle;
+mod headless;
 mod index;
+mod inputs;
+mod keymap;
+mod layout;
+mod log;
+mod pattern;
+mod process;
+mod route;
+mod search;
 
-pub use log::{LogTerminal, SplitBy, SplitFilter};
+pub use {
+    handle::{ExitMode, TerminalHandle},
+    headless::Headless,
+    inputs::{CrosstermEvents, EventSource},
+    keymap::{KeyAction, KeyMap},
+    layout::LayoutMode,
+    log::{LogTerminal, SplitBy, SplitFilter},
+    pattern::{Pattern, TabPattern},
+    route::{Fields, SpanScope},
+};
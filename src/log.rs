@@ -1,89 +1,479 @@
 use {
     crate::{
-        draw::{DrawEvent, MAX_LINES, draw_thread},
-        inputs::inputs_thread,
+        draw::{DrawEvent, DrawOptions, PanicReport, TraceLabel, draw_thread, set_panic_hook},
+        handle::{ExitMode, Shutdown, TerminalHandle},
+        headless::Headless,
+        inputs::{CrosstermEvents, EventSource, inputs_thread},
+        keymap::KeyMap,
+        layout::LayoutMode,
+        pattern::TabPattern,
+        process::{self, Children},
+        route::{Fields, SpanScope},
     },
+    ratatui::backend::Backend,
     std::{
-        collections::VecDeque,
+        cell::RefCell,
         io::{self, Write},
         marker::PhantomData,
-        sync::{Arc, Mutex, Once, mpsc},
+        mem,
+        path::PathBuf,
+        process::Command,
+        sync::{Arc, OnceLock, mpsc},
         thread,
     },
-    tracing::Subscriber,
+    tracing::{Dispatch, Metadata, Subscriber, subscriber::SetGlobalDefaultError},
     tracing_subscriber::{
         Layer, Registry,
+        filter::LevelFilter,
         fmt::{
             Layer as FmtLayer, MakeWriter,
             format::{DefaultFields, Format},
         },
-        layer::{Layered, SubscriberExt},
+        layer::SubscriberExt,
+        registry::LookupSpan,
+        reload,
     },
 };
 
-static TRACING: Once = Once::new();
-
 const NAME_NOT_FOUND: &str = "undefined";
 
-pub struct LogTerminal<N, E, V, S> {
+pub struct LogTerminal<N, E, V, S, Sub = Registry> {
     rl: RedirectLayer<V, S>,
-    fmt_layer: FmtLayer<Layered<RedirectLayer<V, S>, Registry>, N, E, ChannelWriter>,
+    fmt_layer: FmtLayer<Sub, N, E, ChannelWriter>,
+    config: Config,
+}
+
+/// Settings of the builder not depending on the layers
+struct Config {
+    max_level: LevelFilter,
+    keymap: KeyMap,
+    mouse: bool,
+    exit_mode: ExitMode,
+    on_exit: Option<Box<dyn FnOnce() + Send>>,
+    children: Children,
+    panic_lines: usize,
+    max_lines: usize,
+    layout: LayoutMode,
+    layout_file: Option<PathBuf>,
+    groups: Vec<Vec<String>>,
+    source: Box<dyn EventSource>,
+    tx: mpsc::Sender<DrawEvent>,
+    rx: mpsc::Receiver<DrawEvent>,
 }
 
-impl<V, S> LogTerminal<DefaultFields, Format, V, S>
+impl<V, S, Sub> LogTerminal<DefaultFields, Format, V, S, Sub>
 where
-    S: PartialEq<String>,
+    S: TabPattern,
     V: AsRef<[S]>,
 {
-    pub fn new(split_by: SplitBy<V, S>) -> LogTerminal<DefaultFields, Format, V, S> {
-        let (rl, cw) = RedirectLayer::new(split_by);
+    pub fn new(split_by: SplitBy<V, S>) -> LogTerminal<DefaultFields, Format, V, S, Sub> {
+        let (rl, cw, rx) = RedirectLayer::new(split_by);
+        let tx = cw.tx.clone();
         let fmt_layer = FmtLayer::new().with_writer(cw);
-        LogTerminal { rl, fmt_layer }
+        LogTerminal {
+            rl,
+            fmt_layer,
+            config: Config {
+                max_level: LevelFilter::DEBUG,
+                keymap: KeyMap::default(),
+                mouse: false,
+                exit_mode: ExitMode::default(),
+                on_exit: None,
+                children: Children::default(),
+                panic_lines: 20,
+                max_lines: 2_000,
+                layout: LayoutMode::default(),
+                layout_file: None,
+                groups: vec![],
+                source: Box::new(CrosstermEvents),
+                tx,
+                rx,
+            },
+        }
     }
 }
 
-impl<N, E, V, S> LogTerminal<N, E, V, S>
+impl<N, E, V, S, Sub> LogTerminal<N, E, V, S, Sub> {
+    /// Spawn `command` and show its stdout and stderr line by line in the tab `name`.
+    ///
+    /// stdin of the child is closed, stderr lines are painted red and the exit status is shown in
+    /// the tab title once the process terminates.
+    pub fn spawn(&self, name: impl Into<String>, command: Command) -> io::Result<()> {
+        process::spawn(
+            name.into(),
+            command,
+            self.config.tx.clone(),
+            self.config.children.clone(),
+        )
+    }
+}
+
+impl<N, E, V, S, Sub> LogTerminal<N, E, V, S, Sub>
 where
     N: Send + Sync + 'static,
     E: Send + Sync + 'static,
-    S: PartialEq<String>,
+    S: TabPattern,
     V: AsRef<[S]>,
+    Sub: Subscriber + for<'a> LookupSpan<'a> + 'static,
     RedirectLayer<V, S>: Send + Sync + 'static,
-    FmtLayer<Layered<RedirectLayer<V, S>, Registry>, N, E, ChannelWriter>:
-        Layer<Layered<RedirectLayer<V, S>, Registry>>,
+    FmtLayer<Sub, N, E, ChannelWriter>: Layer<Sub>,
 {
     pub fn customize_fmt_layer<N1, E1>(
         self,
-        closure: impl FnOnce(
-            FmtLayer<Layered<RedirectLayer<V, S>, Registry>, N, E, ChannelWriter>,
-        )
-            -> FmtLayer<Layered<RedirectLayer<V, S>, Registry>, N1, E1, ChannelWriter>,
-    ) -> LogTerminal<N1, E1, V, S> {
+        closure: impl FnOnce(FmtLayer<Sub, N, E, ChannelWriter>) -> FmtLayer<Sub, N1, E1, ChannelWriter>,
+    ) -> LogTerminal<N1, E1, V, S, Sub> {
         LogTerminal {
             rl: self.rl,
             fmt_layer: closure(self.fmt_layer),
+            config: self.config,
         }
     }
 
-    pub fn with_max_level(mut self, level: tracing::Level) -> LogTerminal<N, E, V, S> {
-        self.rl.max_level = level;
+    /// Initial max level, it can be changed at runtime with [`TerminalHandle::set_max_level`]
+    pub fn with_max_level(mut self, level: tracing::Level) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.max_level = LevelFilter::from_level(level);
         self
     }
 
-    pub fn with_max_lines(self, lines: usize) -> LogTerminal<N, E, V, S> {
-        *MAX_LINES.lock().unwrap() = lines;
+    /// Replace the default key bindings
+    pub fn with_keymap(mut self, keymap: KeyMap) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.keymap = keymap;
         self
     }
 
-    pub fn finish(self) {
-        TRACING.call_once(|| {
-            let subscriber = tracing_subscriber::registry()
-                .with(self.rl)
-                .with(self.fmt_layer);
+    /// Enable the mouse capture to focus the panes, select the tabs, scroll and resize the groups
+    /// with the mouse.
+    ///
+    /// Disabled by default, because the capture takes the text selection and copy away from the
+    /// terminal emulator: while enabled, the text can only be selected by holding the modifier of
+    /// the terminal emulator (usually Shift).
+    pub fn with_mouse(mut self, enabled: bool) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.mouse = enabled;
+        self
+    }
 
-            tracing::subscriber::set_global_default(subscriber)
-                .expect("global subscriber already set");
-        });
+    /// Choose what happens when the user exits the terminal, see [`ExitMode`]
+    pub fn with_exit_mode(mut self, exit_mode: ExitMode) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.exit_mode = exit_mode;
+        self
+    }
+
+    /// Callback run once the user exits the terminal, after the terminal is restored
+    pub fn on_exit(
+        mut self,
+        callback: impl FnOnce() + Send + 'static,
+    ) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.on_exit = Some(Box::new(callback));
+        self
+    }
+
+    /// Rows of each tab printed to stderr after the panic message (20 by default).
+    ///
+    /// The panic hook restoring the terminal is installed by [`LogTerminal::finish`], or by
+    /// [`Headless::set_panic_hook`].
+    pub fn with_panic_lines(mut self, lines: usize) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.panic_lines = lines;
+        self
+    }
+
+    /// Show the events matching `rule` also in the tab `name`, in addition to the tab chosen by the
+    /// [`SplitBy`]. The line is shared between the tabs, its text is not copied.
+    ///
+    /// ```no_run
+    /// # use log_terminal::{LogTerminal, SplitBy, SplitFilter};
+    /// LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+    ///     .with_extra_tab("errors", |metadata| *metadata.level() == tracing::Level::ERROR)
+    ///     .finish();
+    /// ```
+    pub fn with_extra_tab(
+        mut self,
+        name: impl Into<String>,
+        rule: impl Fn(&Metadata<'_>) -> bool + Send + Sync + 'static,
+    ) -> LogTerminal<N, E, V, S, Sub> {
+        self.rl.extra_tabs.push((name.into(), Box::new(rule)));
+        self
+    }
+
+    /// Show every event also in the tab `name`, shown from the start even before the first event
+    pub fn with_all_tab(mut self, name: impl Into<String>) -> LogTerminal<N, E, V, S, Sub> {
+        self.rl.all_tab = Some(name.into());
+        self
+    }
+
+    /// Initial arrangement of the groups, it can be switched with [`crate::KeyAction::CycleLayout`]
+    pub fn with_layout_mode(mut self, layout: LayoutMode) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.layout = layout;
+        self
+    }
+
+    /// Groups of tabs shown from the start, even before their first event, instead of collecting
+    /// every new tab in the first group.
+    ///
+    /// The groups restored by [`LogTerminal::with_layout_file`] take precedence.
+    ///
+    /// ```no_run
+    /// use log_terminal::{LogTerminal, SplitBy, SplitFilter};
+    ///
+    /// LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+    ///     .with_layout([vec!["api", "worker"], vec!["db"], vec!["errors"]])
+    ///     .finish();
+    /// ```
+    pub fn with_layout<G>(
+        mut self,
+        groups: impl IntoIterator<Item = G>,
+    ) -> LogTerminal<N, E, V, S, Sub>
+    where
+        G: IntoIterator,
+        G::Item: Into<String>,
+    {
+        self.config.groups = groups
+            .into_iter()
+            .map(|tabs| tabs.into_iter().map(Into::into).collect())
+            .collect();
+        self
+    }
+
+    /// Restore the groups arranged in a previous run from the file at `path`, and save them there
+    /// whenever they are rearranged.
+    ///
+    /// A tab is placed in its remembered group, with its remembered width, as soon as its first
+    /// event arrives. The file is created on the first change, the tab open in each group is only
+    /// saved along with the groups and on exit.
+    pub fn with_layout_file(mut self, path: impl Into<PathBuf>) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.layout_file = Some(path.into());
+        self
+    }
+
+    /// Read the inputs from `source` instead of the terminal
+    pub fn with_event_source(
+        mut self,
+        source: impl EventSource + 'static,
+    ) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.source = Box::new(source);
+        self
+    }
+
+    /// Rows kept by each tab (2000 by default)
+    pub fn with_max_lines(mut self, lines: usize) -> LogTerminal<N, E, V, S, Sub> {
+        self.config.max_lines = lines;
+        self
+    }
+
+    /// Start the terminal and return its layers bundled in a single [`Layer`], to compose them
+    /// into an existing subscriber instead of the [`Registry`] built by [`LogTerminal::finish`].
+    ///
+    /// ```no_run
+    /// use {
+    ///     log_terminal::{LogTerminal, SplitBy, SplitFilter},
+    ///     tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt},
+    /// };
+    ///
+    /// let (terminal, handle) = LogTerminal::new(SplitBy::Target(SplitFilter::none())).into_layer();
+    ///
+    /// tracing_subscriber::registry()
+    ///     .with(tracing_subscriber::fmt::layer().with_ansi(false).with_writer(std::io::stderr))
+    ///     .with(terminal)
+    ///     .init();
+    /// ```
+    pub fn into_layer(self) -> (impl Layer<Sub> + Send + Sync + 'static, TerminalHandle) {
+        let Parts {
+            layer,
+            handle,
+            threads,
+        } = self.into_parts();
+
+        threads.start();
+
+        (layer, handle)
+    }
+
+    /// Build the layer of [`LogTerminal::into_layer`] with a [`Headless`] terminal rendering its
+    /// events on `backend`, instead of starting the threads on the real terminal.
+    ///
+    /// The events are recorded by the subscriber holding the layer, [`Headless::dispatch`] of the
+    /// returned terminal records nothing.
+    pub fn into_headless_layer<B: Backend>(
+        self,
+        backend: B,
+        source: impl EventSource + 'static,
+    ) -> io::Result<(impl Layer<Sub> + Send + Sync + 'static, Headless<B>)> {
+        let Parts {
+            layer,
+            handle,
+            threads,
+        } = self.into_parts();
+
+        let headless = Headless::new(
+            backend,
+            threads.options,
+            Box::new(source),
+            threads.rx,
+            Dispatch::none(),
+            handle,
+            threads.panic_lines,
+        )?;
+
+        Ok((layer, headless))
+    }
+
+    fn into_parts(self) -> Parts<impl Layer<Sub> + Send + Sync + 'static> {
+        let config = self.config;
+
+        let (level_filter, level_handle) = reload::Layer::new(config.max_level);
+
+        let shutdown = Arc::new(Shutdown::default());
+
+        let handle = TerminalHandle::new(
+            level_handle,
+            config.tx.clone(),
+            shutdown.clone(),
+            config.children.clone(),
+        );
+
+        let tabs = self.rl.all_tab.iter().cloned().collect();
+
+        Parts {
+            layer: self.rl.and_then(self.fmt_layer).with_filter(level_filter),
+            handle,
+            threads: Threads {
+                options: DrawOptions {
+                    mouse: config.mouse,
+                    keymap: config.keymap,
+                    exit_mode: config.exit_mode,
+                    on_exit: config.on_exit,
+                    shutdown,
+                    children: config.children,
+                    report: PanicReport::default(),
+                    max_lines: config.max_lines,
+                    tabs,
+                    groups: config.groups,
+                    layout: config.layout,
+                    layout_file: config.layout_file,
+                },
+                source: config.source,
+                tx: config.tx,
+                rx: config.rx,
+                panic_lines: config.panic_lines,
+            },
+        }
+    }
+}
+
+impl<N, E, V, S> LogTerminal<N, E, V, S>
+where
+    N: Send + Sync + 'static,
+    E: Send + Sync + 'static,
+    S: TabPattern,
+    V: AsRef<[S]>,
+    RedirectLayer<V, S>: Send + Sync + 'static,
+    FmtLayer<Registry, N, E, ChannelWriter>: Layer<Registry>,
+{
+    /// Start the terminal and set its subscriber as global default.
+    ///
+    /// The later calls start nothing and return the handle of the first terminal. Panics if
+    /// another global default subscriber is already set, see [`LogTerminal::try_finish`] and
+    /// [`LogTerminal::into_layer`] to add the terminal to an existing subscriber.
+    pub fn finish(self) -> TerminalHandle {
+        static FINISHED: OnceLock<TerminalHandle> = OnceLock::new();
+
+        FINISHED
+            .get_or_init(|| self.try_finish().expect("global subscriber already set"))
+            .clone()
+    }
+
+    /// Set the subscriber of the terminal as global default, then start the terminal.
+    ///
+    /// Nothing is started when a global default subscriber is already set.
+    pub fn try_finish(self) -> Result<TerminalHandle, SetGlobalDefaultError> {
+        let Parts {
+            layer,
+            handle,
+            threads,
+        } = self.into_parts();
+
+        tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer))?;
+
+        threads.start();
+
+        Ok(handle)
+    }
+
+    /// Start the terminal and return its subscriber instead of setting it as global default.
+    ///
+    /// The subscriber can be used with [`tracing::subscriber::with_default`] or extended with
+    /// other layers through [`SubscriberExt::with`].
+    pub fn finish_subscriber(
+        self,
+    ) -> (
+        impl Subscriber + for<'a> LookupSpan<'a> + Send + Sync + 'static,
+        TerminalHandle,
+    ) {
+        let (layer, handle) = self.into_layer();
+
+        (tracing_subscriber::registry().with(layer), handle)
+    }
+
+    /// Build a [`Headless`] terminal rendering on `backend` and reading the inputs from `source`.
+    ///
+    /// `source` replaces the one set with [`LogTerminal::with_event_source`], which is ignored.
+    ///
+    /// Nothing is spawned and the subscriber is not set as global default, see [`Headless`].
+    pub fn finish_headless<B: Backend>(
+        self,
+        backend: B,
+        source: impl EventSource + 'static,
+    ) -> io::Result<Headless<B>> {
+        let Parts {
+            layer,
+            handle,
+            threads,
+        } = self.into_parts();
+
+        Headless::new(
+            backend,
+            threads.options,
+            Box::new(source),
+            threads.rx,
+            Dispatch::new(tracing_subscriber::registry().with(layer)),
+            handle,
+            threads.panic_lines,
+        )
+    }
+}
+
+/// Layers and state of the terminal, before it is started
+struct Parts<L> {
+    layer: L,
+    handle: TerminalHandle,
+    threads: Threads,
+}
+
+/// State moved to the inputs and draw threads
+struct Threads {
+    options: DrawOptions,
+    source: Box<dyn EventSource>,
+    tx: mpsc::Sender<DrawEvent>,
+    rx: mpsc::Receiver<DrawEvent>,
+    panic_lines: usize,
+}
+
+impl Threads {
+    /// Install the panic hook and spawn the inputs and draw threads, which take the terminal
+    fn start(self) {
+        set_panic_hook(self.options.report.clone(), self.panic_lines);
+
+        let Threads {
+            options,
+            source,
+            tx,
+            rx,
+            ..
+        } = self;
+
+        let keymap = options.keymap.clone();
+
+        thread::spawn(move || inputs_thread(tx, keymap, source));
+        thread::spawn(move || draw_thread(rx, options));
     }
 }
 
@@ -94,6 +484,31 @@ pub enum SplitBy<V, S> {
     TargetPrefix(SplitFilter<V, S>),
     /// Tabs are splitted by the `span` prefix
     SpanPrefix(SplitFilter<V, S>),
+    /// Tabs are splitted by the names of `depth` spans joined by `/`, e.g. `request/db_query`.
+    ///
+    /// The spans are taken from the root, or ending at the innermost span when `innermost` is set.
+    SpanPath {
+        depth: usize,
+        innermost: bool,
+        filter: SplitFilter<V, S>,
+    },
+    /// Tabs are splitted by the level, named `ERROR`, `WARN`, `INFO`, `DEBUG` and `TRACE`
+    Level(SplitFilter<V, S>),
+    /// Tabs are splitted by the value of the event field with the given name
+    Field(&'static str, SplitFilter<V, S>),
+    /// Tabs are named by the closure, the events for which it returns `None` are not shown
+    Custom(CustomSplit),
+}
+
+type CustomSplit = Box<dyn Fn(&Metadata<'_>, &SpanScope, &Fields) -> Option<String> + Send + Sync>;
+
+impl SplitBy<Vec<String>, String> {
+    /// Tabs are named by `split`, see [`SplitBy::Custom`]
+    pub fn custom(
+        split: impl Fn(&Metadata<'_>, &SpanScope, &Fields) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        Self::Custom(Box::new(split))
+    }
 }
 
 #[non_exhaustive]
@@ -102,13 +517,15 @@ pub enum SplitFilter<V, S> {
     WhiteList(V, PhantomData<S>),
     /// Based on [`SplitBy`], show only the tabs that are not in the blacklist
     BlackList(V, PhantomData<S>),
+    /// Show all the tabs, the names matched by a [`TabPattern`] with an alias are renamed
+    Aliases(V, PhantomData<S>),
     /// Show all the tabs
     None,
 }
 
 impl<V, S> SplitFilter<V, S>
 where
-    S: PartialEq<String>,
+    S: TabPattern,
     V: AsRef<[S]>,
 {
     /// Based on [`SplitBy`], show only the tabs that are in the whitelist
@@ -120,6 +537,11 @@ where
     pub fn blacklist(items: V) -> Self {
         Self::BlackList(items, PhantomData)
     }
+
+    /// Show all the tabs, the names matched by a [`TabPattern`] with an alias are renamed
+    pub fn aliases(items: V) -> Self {
+        Self::Aliases(items, PhantomData)
+    }
 }
 
 impl SplitFilter<Vec<String>, String> {
@@ -131,60 +553,70 @@ impl SplitFilter<Vec<String>, String> {
 
 impl<V, S> SplitFilter<V, S>
 where
-    S: PartialEq<String>,
+    S: TabPattern,
     V: AsRef<[S]>,
 {
-    pub fn filter<'a>(&self, target: String) -> Option<String> {
+    /// Name of the tab of `target`, `None` if it is not shown
+    pub fn filter(&self, target: String) -> Option<String> {
         match self {
             SplitFilter::WhiteList(items, _) => {
-                if items.as_ref().iter().any(|item| item == &target) {
-                    Some(target)
-                } else {
-                    None
-                }
+                Self::find(items, &target).map(|item| Self::rename(item, target))
             },
             SplitFilter::BlackList(items, _) => {
-                if items.as_ref().iter().any(|item| item == &target) {
+                if Self::find(items, &target).is_some() {
                     None
                 } else {
                     Some(target)
                 }
             },
+            SplitFilter::Aliases(items, _) => match Self::find(items, &target) {
+                Some(item) => Some(Self::rename(item, target)),
+                None => Some(target),
+            },
             SplitFilter::None => Some(target),
         }
     }
+
+    fn find<'a>(items: &'a V, target: &str) -> Option<&'a S> {
+        items.as_ref().iter().find(|item| item.is_match(target))
+    }
+
+    fn rename(item: &S, target: String) -> String {
+        item.alias().map_or(target, str::to_string)
+    }
+}
+
+thread_local! {
+    /// Destination of the event being formatted on this thread, set by the `RedirectLayer` and
+    /// taken by the `ChannelWriter` of the fmt layer running right after it
+    static ROUTE: RefCell<Option<TraceLabel>> = const { RefCell::new(None) };
 }
 
 pub struct RedirectLayer<V, S> {
-    max_level: tracing::Level,
     split_by: SplitBy<V, S>,
-    events: Arc<Mutex<VecDeque<Option<String>>>>,
+    /// Tabs receiving the events matching their rule, in addition to the [`SplitBy`] one
+    extra_tabs: Vec<(String, TabRule)>,
+    /// Tab receiving every event
+    all_tab: Option<String>,
 }
 
+type TabRule = Box<dyn Fn(&Metadata<'_>) -> bool + Send + Sync>;
+
 impl<V, S> RedirectLayer<V, S>
 where
-    S: PartialEq<String>,
+    S: TabPattern,
     V: AsRef<[S]>,
 {
-    pub fn new(split_by: SplitBy<V, S>) -> (Self, ChannelWriter) {
+    pub(crate) fn new(split_by: SplitBy<V, S>) -> (Self, ChannelWriter, mpsc::Receiver<DrawEvent>) {
         let (tx, rx) = mpsc::channel();
 
-        let events: Arc<Mutex<VecDeque<Option<String>>>> = Default::default();
-
-        let _events = events.clone();
-        let _tx = tx.clone();
-
-        thread::spawn(move || inputs_thread(_tx));
-        thread::spawn(move || draw_thread(_events, rx));
+        let rl = Self {
+            split_by,
+            extra_tabs: vec![],
+            all_tab: None,
+        };
 
-        (
-            Self {
-                max_level: tracing::Level::DEBUG,
-                events,
-                split_by,
-            },
-            ChannelWriter { tx },
-        )
+        (rl, ChannelWriter { tx }, rx)
     }
 
     fn filter<Sub>(
@@ -219,6 +651,52 @@ where
                 };
                 filter.filter(str)
             },
+            SplitBy::SpanPath {
+                depth,
+                innermost,
+                filter,
+            } => {
+                let names = _ctx
+                    .event_scope(event)
+                    .map(|scope| {
+                        scope
+                            .from_root()
+                            .map(|span| span.name())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+
+                let names = if *innermost {
+                    &names[names.len().saturating_sub(*depth)..]
+                } else {
+                    &names[..names.len().min(*depth)]
+                };
+
+                if names.is_empty() {
+                    filter.filter(NAME_NOT_FOUND.to_string())
+                } else {
+                    filter.filter(names.join("/"))
+                }
+            },
+            SplitBy::Level(filter) => filter.filter(event.metadata().level().to_string()),
+            SplitBy::Field(name, filter) => filter.filter(
+                Fields::record(event)
+                    .get(name)
+                    .unwrap_or(NAME_NOT_FOUND)
+                    .to_string(),
+            ),
+            SplitBy::Custom(split) => {
+                let spans = _ctx
+                    .event_scope(event)
+                    .map(|scope| scope.from_root().map(|span| span.metadata()).collect())
+                    .unwrap_or_default();
+
+                split(
+                    event.metadata(),
+                    &SpanScope::new(spans),
+                    &Fields::record(event),
+                )
+            },
         }
     }
 }
@@ -226,26 +704,37 @@ where
 impl<S, V, Sub> Layer<Sub> for RedirectLayer<V, S>
 where
     Self: 'static,
-    S: PartialEq<String>,
+    S: TabPattern,
     V: AsRef<[S]>,
     Sub: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
 {
-    fn enabled(
-        &self,
-        metadata: &tracing::Metadata<'_>,
-        _ctx: tracing_subscriber::layer::Context<'_, Sub>,
-    ) -> bool {
-        metadata.level() <= &self.max_level
-    }
-
     fn on_event(
         &self,
         event: &tracing::Event<'_>,
         _ctx: tracing_subscriber::layer::Context<'_, Sub>,
     ) {
-        let name = self.filter(event, _ctx);
+        let mut names = self.filter(event, _ctx).into_iter().collect::<Vec<_>>();
+
+        let extra_tabs = self
+            .extra_tabs
+            .iter()
+            .filter(|(_, rule)| rule(event.metadata()))
+            .map(|(name, _)| name)
+            .chain(&self.all_tab);
 
-        self.events.lock().unwrap().push_back(name);
+        for name in extra_tabs {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+
+        let label = (!names.is_empty()).then(|| TraceLabel {
+            names,
+            level: *event.metadata().level(),
+        });
+
+        // an event filtered out clears the route, so its writes are discarded
+        ROUTE.with_borrow_mut(|route| *route = label);
     }
 }
 
@@ -254,16 +743,31 @@ pub struct ChannelWriter {
 }
 
 impl<'a> MakeWriter<'a> for ChannelWriter {
-    type Writer = &'a Self;
+    type Writer = EventWriter<'a>;
 
     fn make_writer(&'a self) -> Self::Writer {
-        self
+        EventWriter {
+            tx: &self.tx,
+            route: ROUTE.with_borrow_mut(Option::take),
+            buf: vec![],
+        }
     }
 }
 
-impl<'a> Write for &'a ChannelWriter {
+/// Collect the writes of a single event, sent with its destination once dropped.
+///
+/// Writes without a destination (filtered events, span events of the fmt layer) are discarded.
+pub struct EventWriter<'a> {
+    tx: &'a mpsc::Sender<DrawEvent>,
+    route: Option<TraceLabel>,
+    buf: Vec<u8>,
+}
+
+impl Write for EventWriter<'_> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.tx.send(DrawEvent::Trace(buf.to_vec())).unwrap();
+        if self.route.is_some() {
+            self.buf.extend_from_slice(buf);
+        }
         Ok(buf.len())
     }
 
@@ -271,3 +775,59 @@ impl<'a> Write for &'a ChannelWriter {
         Ok(())
     }
 }
+
+impl Drop for EventWriter<'_> {
+    fn drop(&mut self) {
+        if let Some(route) = self.route.take()
+            && !self.buf.is_empty()
+        {
+            let _ = self
+                .tx
+                .send(DrawEvent::Trace(route, mem::take(&mut self.buf)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Write, tracing::Level};
+
+    fn label() -> TraceLabel {
+        TraceLabel {
+            names: vec!["api".to_string()],
+            level: Level::INFO,
+        }
+    }
+
+    #[test]
+    fn event_writer_joins_the_writes() {
+        let (tx, rx) = mpsc::channel();
+
+        let writer = ChannelWriter { tx };
+
+        ROUTE.with_borrow_mut(|route| *route = Some(label()));
+
+        {
+            let mut event = writer.make_writer();
+
+            for chunk in ["INFO api: ", "first ", "request\n"] {
+                event.write_all(chunk.as_bytes()).unwrap();
+            }
+
+            // nothing is sent before the event is fully written
+            assert!(rx.try_recv().is_err());
+        }
+
+        let Ok(DrawEvent::Trace(route, line)) = rx.try_recv() else {
+            panic!("the event was not sent");
+        };
+
+        assert_eq!(route.names, ["api"]);
+        assert_eq!(line, b"INFO api: first request\n");
+
+        // the route is consumed, the next writes have no destination and are discarded
+        writer.make_writer().write_all(b"span event\n").unwrap();
+
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use regex::Regex;
+
+/// Item of the [`crate::SplitFilter`] lists, matched against the tab names.
+///
+/// Implemented by the strings, matching the exact name, and by [`Pattern`].
+pub trait TabPattern {
+    fn is_match(&self, name: &str) -> bool;
+
+    /// Name of the tab receiving the matched names, `None` to keep the name
+    fn alias(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl<T: AsRef<str>> TabPattern for T {
+    fn is_match(&self, name: &str) -> bool {
+        self.as_ref() == name
+    }
+}
+
+/// Exact, glob or regex pattern, optionally collapsing the matched names into an alias
+///
+/// ```
+/// # use log_terminal::{Pattern, SplitBy, SplitFilter};
+/// SplitBy::Target(SplitFilter::whitelist(vec![
+///     Pattern::exact("api"),
+///     Pattern::glob("my_crate::net::*"),
+///     Pattern::glob("hyper::*").alias("http"),
+/// ]));
+/// ```
+pub struct Pattern {
+    kind: PatternKind,
+    alias: Option<String>,
+}
+
+enum PatternKind {
+    Exact(String),
+    /// Globs are compiled to a regex
+    Glob(Regex),
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn exact(name: impl Into<String>) -> Self {
+        Self::new(PatternKind::Exact(name.into()))
+    }
+
+    /// `*` matches any sequence of characters, `?` any single character
+    pub fn glob(glob: &str) -> Self {
+        let regex = glob
+            .split('*')
+            .map(|part| {
+                part.split('?')
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect::<Vec<_>>()
+            .join(".*");
+
+        let regex = Regex::new(&format!("^{regex}$")).expect("escaped glob is a valid regex");
+
+        Self::new(PatternKind::Glob(regex))
+    }
+
+    /// The regex is not anchored, use `^` and `$` to match the whole name
+    pub fn regex(regex: &str) -> Result<Self, regex::Error> {
+        Ok(Self::new(PatternKind::Regex(Regex::new(regex)?)))
+    }
+
+    /// Show the matched names in the tab `alias`
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    fn new(kind: PatternKind) -> Self {
+        Self { kind, alias: None }
+    }
+}
+
+impl TabPattern for Pattern {
+    fn is_match(&self, name: &str) -> bool {
+        match &self.kind {
+            PatternKind::Exact(exact) => exact == name,
+            PatternKind::Glob(regex) | PatternKind::Regex(regex) => regex.is_match(name),
+        }
+    }
+
+    fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+}
@@ -0,0 +1,155 @@
+use {
+    crate::draw::DrawEvent,
+    std::{
+        io::{self, BufRead, BufReader, Read},
+        process::{Child, Command, ExitStatus, Stdio},
+        sync::{Arc, Mutex, mpsc},
+        thread,
+        time::Duration,
+    },
+};
+
+pub(crate) enum ProcessEvent {
+    Started,
+    Stdout(String),
+    Stderr(String),
+    Exited(Option<ExitStatus>),
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum ProcessStatus {
+    Running,
+    Exited(Option<ExitStatus>),
+}
+
+impl ProcessStatus {
+    pub fn label(&self) -> String {
+        match self {
+            ProcessStatus::Running => "running".to_string(),
+            ProcessStatus::Exited(Some(status)) => match status.code() {
+                Some(code) => format!("exit {code}"),
+                None => "killed".to_string(),
+            },
+            ProcessStatus::Exited(None) => "unknown".to_string(),
+        }
+    }
+}
+
+/// Interval between the checks of the exit of a child
+const WAIT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Child shared between its waiting thread and [`Children::kill_all`]
+type SharedChild = (u32, Arc<Mutex<Child>>);
+
+/// Processes spawned by the terminal and still running
+#[derive(Clone, Default)]
+pub(crate) struct Children(Arc<Mutex<Vec<SharedChild>>>);
+
+impl Children {
+    /// Kill every running process and wait for its termination
+    pub fn kill_all(&self) {
+        let children = self.0.lock().unwrap().drain(..).collect::<Vec<_>>();
+
+        for (_, child) in children {
+            let mut child = child.lock().unwrap();
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    fn remove(&self, id: u32) {
+        self.0
+            .lock()
+            .unwrap()
+            .retain(|(child_id, _)| *child_id != id);
+    }
+}
+
+pub(crate) fn spawn(
+    name: String,
+    mut command: Command,
+    tx: mpsc::Sender<DrawEvent>,
+    children: Children,
+) -> io::Result<()> {
+    // stdin is not forwarded, the terminal input belongs to the inputs thread
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+
+    let id = child.id();
+    // the lock is only taken to poll or kill the child, so it can be killed while it runs
+    let child = Arc::new(Mutex::new(child));
+
+    children.0.lock().unwrap().push((id, child.clone()));
+
+    let _ = tx.send(DrawEvent::Process(name.clone(), ProcessEvent::Started));
+
+    thread::spawn(move || {
+        let stderr_thread = {
+            let name = name.clone();
+            let tx = tx.clone();
+            thread::spawn(move || forward_lines(stderr, ProcessEvent::Stderr, name, tx))
+        };
+
+        forward_lines(stdout, ProcessEvent::Stdout, name.clone(), tx.clone());
+
+        // wait for both streams to be drained so the exit status is always the last line
+        let _ = stderr_thread.join();
+
+        let status = wait(&child);
+
+        children.remove(id);
+
+        let _ = tx.send(DrawEvent::Process(name, ProcessEvent::Exited(status)));
+    });
+
+    Ok(())
+}
+
+/// Wait for the exit of the child without holding its lock in the meantime
+fn wait(child: &Mutex<Child>) -> Option<ExitStatus> {
+    loop {
+        match child.lock().unwrap().try_wait() {
+            Ok(Some(status)) => return Some(status),
+            Ok(None) => {},
+            Err(_) => return None,
+        }
+
+        thread::sleep(WAIT_INTERVAL);
+    }
+}
+
+fn forward_lines(
+    stream: impl Read,
+    event: fn(String) -> ProcessEvent,
+    name: String,
+    tx: mpsc::Sender<DrawEvent>,
+) {
+    let mut reader = BufReader::new(stream);
+    let mut buf = vec![];
+
+    loop {
+        buf.clear();
+
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf)
+                    .trim_end_matches(['\n', '\r'])
+                    .to_string();
+
+                if tx
+                    .send(DrawEvent::Process(name.clone(), event(line)))
+                    .is_err()
+                {
+                    break;
+                }
+            },
+        }
+    }
+}
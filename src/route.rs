@@ -0,0 +1,81 @@
+use {
+    std::fmt,
+    tracing::{
+        Event, Metadata,
+        field::{Field, Visit},
+    },
+};
+
+/// Spans containing an event, from the root to the innermost one
+pub struct SpanScope {
+    spans: Vec<&'static Metadata<'static>>,
+}
+
+impl SpanScope {
+    pub(crate) fn new(spans: Vec<&'static Metadata<'static>>) -> Self {
+        Self { spans }
+    }
+
+    /// Metadata of the spans, from the root to the innermost one
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'static Metadata<'static>> + '_ {
+        self.spans.iter().copied()
+    }
+
+    /// Names of the spans, from the root to the innermost one
+    pub fn names(&self) -> impl DoubleEndedIterator<Item = &'static str> + '_ {
+        self.iter().map(|span| span.name())
+    }
+
+    pub fn root(&self) -> Option<&'static Metadata<'static>> {
+        self.spans.first().copied()
+    }
+
+    pub fn innermost(&self) -> Option<&'static Metadata<'static>> {
+        self.spans.last().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+/// Fields of an event, recorded as strings.
+///
+/// String values are recorded without quotes, the other values with their [`fmt::Debug`].
+pub struct Fields {
+    values: Vec<(&'static str, String)>,
+}
+
+impl Fields {
+    pub(crate) fn record(event: &Event<'_>) -> Self {
+        let mut fields = Self { values: vec![] };
+
+        event.record(&mut fields);
+
+        fields
+    }
+
+    /// Value of the field `name`, the message is recorded as `message`
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.values
+            .iter()
+            .map(|(field, value)| (*field, value.as_str()))
+    }
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.values.push((field.name(), value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.values.push((field.name(), format!("{value:?}")));
+    }
+}
@@ -0,0 +1,198 @@
+use {
+    ratatui::{
+        style::{Style, Stylize},
+        text::{Line, Span},
+    },
+    regex::Regex,
+};
+
+pub(crate) enum SearchEvent {
+    Start,
+    Input(char),
+    Backspace,
+    Confirm,
+    Next,
+    Previous,
+    Clear,
+}
+
+pub(crate) enum FilterEvent {
+    Start,
+    Input(char),
+    Backspace,
+    ToggleRegex,
+    Confirm,
+    Clear,
+}
+
+pub(crate) struct Search {
+    pub query: String,
+    pub editing: bool,
+    /// Row index of the match currently shown
+    pub current: Option<usize>,
+    /// Row offset when the search started, incremental matches are looked up from here
+    pub origin: usize,
+}
+
+impl Search {
+    pub fn new(origin: usize) -> Self {
+        Self {
+            query: String::new(),
+            editing: true,
+            current: None,
+            origin,
+        }
+    }
+
+    pub fn prompt(&self, matches: usize) -> String {
+        let cursor = if self.editing {
+            "_"
+        } else {
+            ""
+        };
+
+        format!("/{}{cursor} [{matches} matches]", self.query)
+    }
+}
+
+pub(crate) enum FilterPattern {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl FilterPattern {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            FilterPattern::Substring(pattern) => text.contains(pattern.as_str()),
+            FilterPattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+pub(crate) struct Filter {
+    pub input: String,
+    pub regex: bool,
+    pub editing: bool,
+    /// `None` while the input is empty or is not a valid regex
+    pattern: Option<FilterPattern>,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self {
+            input: String::new(),
+            regex: false,
+            editing: true,
+            pattern: None,
+        }
+    }
+
+    /// Compile the input, must be called every time `input` or `regex` change
+    pub fn update(&mut self) {
+        self.pattern = if self.input.is_empty() {
+            None
+        } else if self.regex {
+            Regex::new(&self.input).ok().map(FilterPattern::Regex)
+        } else {
+            Some(FilterPattern::Substring(self.input.clone()))
+        };
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(text))
+    }
+
+    pub fn label(&self) -> String {
+        let kind = if self.regex {
+            "Filter (regex)"
+        } else {
+            "Filter"
+        };
+
+        format!("{kind}: {}", self.input)
+    }
+
+    pub fn prompt(&self) -> String {
+        let invalid = if self.regex && !self.input.is_empty() && self.pattern.is_none() {
+            " [invalid regex]"
+        } else {
+            ""
+        };
+
+        format!("&{}_{invalid} [tab: toggle regex]", self.input)
+    }
+}
+
+pub(crate) fn match_style(current: bool) -> Style {
+    if current {
+        Style::default().black().on_light_red()
+    } else {
+        Style::default().black().on_yellow()
+    }
+}
+
+/// Patch `style` on every occurrence of `query` in `line`, splitting the spans where needed.
+///
+/// `line` is a single wrapped line, an occurrence split by the wrap is not found.
+pub(crate) fn highlight(line: Line<'static>, query: &str, style: Style) -> Line<'static> {
+    let plain = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect::<String>();
+
+    let matches = plain
+        .match_indices(query)
+        .map(|(start, found)| (start, start + found.len()))
+        .collect::<Vec<_>>();
+
+    if matches.is_empty() {
+        return line;
+    }
+
+    let mut spans = vec![];
+
+    let mut position = 0;
+
+    for span in line.spans.iter() {
+        let start = position;
+        let end = position + span.content.len();
+        position = end;
+
+        let mut cursor = start;
+
+        for (match_start, match_end) in matches.iter().copied() {
+            if match_end <= cursor || match_start >= end {
+                continue;
+            }
+
+            let from = match_start.max(cursor);
+            let to = match_end.min(end);
+
+            if from > cursor {
+                spans.push(Span::styled(
+                    span.content[cursor - start..from - start].to_string(),
+                    span.style,
+                ));
+            }
+
+            spans.push(Span::styled(
+                span.content[from - start..to - start].to_string(),
+                span.style.patch(style),
+            ));
+
+            cursor = to;
+        }
+
+        if cursor < end {
+            spans.push(Span::styled(
+                span.content[cursor - start..].to_string(),
+                span.style,
+            ));
+        }
+    }
+
+    Line { spans, ..line }
+}
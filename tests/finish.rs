@@ -0,0 +1,10 @@
+use log_terminal::{LogTerminal, SplitBy, SplitFilter};
+
+#[test]
+fn try_finish_with_global_subscriber() {
+    tracing::subscriber::set_global_default(tracing_subscriber::registry()).unwrap();
+
+    let result = LogTerminal::new(SplitBy::Target(SplitFilter::none())).try_finish();
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,1450 @@
+use {
+    crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    log_terminal::{
+        EventSource, ExitMode, Headless, LayoutMode, LogTerminal, Pattern, SplitBy, SplitFilter,
+        TabPattern,
+    },
+    ratatui::{backend::TestBackend, style::Color},
+    std::{
+        collections::VecDeque,
+        io,
+        process::Command,
+        sync::{Arc, Mutex, mpsc},
+        thread,
+        time::{Duration, Instant},
+    },
+    tracing_subscriber::layer::SubscriberExt,
+};
+
+fn terminal<V, S>(
+    split_by: SplitBy<V, S>,
+    width: u16,
+    height: u16,
+) -> (Headless<TestBackend>, mpsc::Sender<Event>)
+where
+    S: TabPattern + Send + Sync + 'static,
+    V: AsRef<[S]> + Send + Sync + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+
+    let terminal = LogTerminal::new(split_by)
+        .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+        .finish_headless(TestBackend::new(width, height), rx)
+        .unwrap();
+
+    (terminal, tx)
+}
+
+fn key(tx: &mpsc::Sender<Event>, code: KeyCode, modifiers: KeyModifiers) {
+    tx.send(Event::Key(KeyEvent::new(code, modifiers))).unwrap();
+}
+
+fn contains(terminal: &Headless<TestBackend>, text: &str) -> bool {
+    terminal.lines().iter().any(|line| line.contains(text))
+}
+
+fn mouse(tx: &mpsc::Sender<Event>, kind: MouseEventKind, column: u16, row: u16) {
+    tx.send(Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }))
+    .unwrap();
+}
+
+#[test]
+fn split_by_target() {
+    let (mut terminal, _tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+    });
+
+    terminal.step().unwrap();
+
+    let lines = terminal.lines();
+
+    assert!(lines[0].contains("api") && lines[0].contains("db"));
+    // the first tab is selected
+    assert!(contains(&terminal, "INFO api: request"));
+    assert!(!contains(&terminal, "INFO db: query"));
+}
+
+#[test]
+fn split_filter_whitelist() {
+    let (mut terminal, _tx) = terminal(
+        SplitBy::Target(SplitFilter::whitelist(vec!["api".to_string()])),
+        60,
+        10,
+    );
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+    });
+
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "INFO api: request"));
+    assert!(!terminal.lines()[0].contains("db"));
+}
+
+#[test]
+fn scroll() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        for index in 0..50 {
+            tracing::info!(target: "api", "line {index}");
+        }
+    });
+
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "line 49"));
+    assert!(!contains(&terminal, "Scrolling"));
+
+    key(&tx, KeyCode::Up, KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    assert!(!contains(&terminal, "line 49"));
+    assert!(contains(&terminal, "line 39"));
+    assert!(contains(&terminal, "Scrolling: 10"));
+
+    // the lines received while scrolling don't move the view
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "line 50");
+    });
+
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "line 39"));
+
+    key(&tx, KeyCode::Down, KeyModifiers::ALT);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "line 50"));
+    assert!(!contains(&terminal, "Scrolling"));
+}
+
+#[test]
+fn move_select() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+    });
+
+    terminal.step().unwrap();
+
+    // a single group with both tabs
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 1);
+
+    key(&tx, KeyCode::Right, KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    // the selected tab is moved in a new group on the right
+    let lines = terminal.lines();
+
+    assert_eq!(lines[1].matches('╮').count(), 2);
+    assert!(lines[0].find("db").unwrap() < lines[0].find("api").unwrap());
+    assert!(contains(&terminal, "INFO api: request"));
+    assert!(contains(&terminal, "INFO db: query"));
+
+    // and moved back
+    key(&tx, KeyCode::Left, KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 1);
+}
+
+#[test]
+fn exit() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    key(&tx, KeyCode::Char('c'), KeyModifiers::CONTROL);
+    terminal.step().unwrap();
+
+    assert!(terminal.is_closed());
+    assert!(terminal.handle().is_shutdown());
+}
+
+#[test]
+fn exit_kills_children() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    let mut command = Command::new("sh");
+    // the child closes its streams, only its exit is left to wait for
+    command.args(["-c", "exec >&- 2>&-; sleep 20"]);
+
+    terminal.handle().spawn("sleep", command).unwrap();
+    terminal.step().unwrap();
+
+    thread::sleep(Duration::from_millis(300));
+
+    let start = Instant::now();
+
+    key(&tx, KeyCode::Char('c'), KeyModifiers::CONTROL);
+    terminal.step().unwrap();
+
+    assert!(terminal.is_closed());
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn exit_runs_on_exit() {
+    let (tx, rx) = mpsc::channel();
+    let (exited_tx, exited) = mpsc::channel();
+
+    let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .on_exit(move || exited_tx.send(()).unwrap())
+        .finish_headless(TestBackend::new(60, 10), rx)
+        .unwrap();
+
+    terminal.step().unwrap();
+
+    assert!(!terminal.handle().is_shutdown());
+    assert!(exited.try_recv().is_err());
+
+    key(&tx, KeyCode::Char('c'), KeyModifiers::CONTROL);
+    terminal.step().unwrap();
+
+    assert!(terminal.is_closed());
+    assert!(terminal.handle().is_shutdown());
+    assert!(exited.try_recv().is_ok());
+}
+
+/// Set in the child process of `exit_mode_stderr`
+const EXIT_CHILD: &str = "LOG_TERMINAL_EXIT_CHILD";
+
+#[test]
+#[ignore = "run by exit_mode_stderr in a child process"]
+fn exit_mode_stderr_child() {
+    if std::env::var_os(EXIT_CHILD).is_none() {
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel();
+
+    let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+        .with_exit_mode(ExitMode::Stderr)
+        .finish_headless(TestBackend::new(60, 10), rx)
+        .unwrap();
+
+    let mut command = Command::new("sh");
+    command.args(["-c", "sleep 0.3; echo still running"]);
+
+    terminal.handle().spawn("child", command).unwrap();
+
+    key(&tx, KeyCode::Char('c'), KeyModifiers::CONTROL);
+    terminal.step().unwrap();
+
+    assert!(terminal.is_closed());
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "after exit");
+    });
+
+    // the child is not killed, its output follows the events on stderr
+    for _ in 0..50 {
+        thread::sleep(Duration::from_millis(20));
+        terminal.step().unwrap();
+    }
+}
+
+#[test]
+fn exit_mode_stderr() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "--exact",
+            "exit_mode_stderr_child",
+            "--ignored",
+            "--nocapture",
+        ])
+        .env(EXIT_CHILD, "1")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success());
+    assert!(stderr.contains("INFO api: after exit"));
+    assert!(stderr.contains("[child] still running"));
+    assert!(stderr.contains("[child] exit 0"));
+}
+
+#[test]
+fn spawn_streams_output() {
+    let (mut terminal, _tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    let mut command = Command::new("sh");
+    command.args([
+        "-c",
+        "echo to stdout; echo to stderr >&2; sleep 0.3; exit 3",
+    ]);
+
+    terminal.handle().spawn("child", command).unwrap();
+    terminal.step().unwrap();
+
+    assert!(terminal.lines()[0].contains("child [running]"));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while !contains(&terminal, "[exit 3]") {
+        assert!(Instant::now() < deadline, "the exit status is never shown");
+        thread::sleep(Duration::from_millis(20));
+        terminal.step().unwrap();
+    }
+
+    assert!(terminal.lines()[0].contains("child [exit 3]"));
+    assert!(contains(&terminal, "to stdout"));
+    assert!(contains(&terminal, "to stderr"));
+
+    let foreground = |text: &str| {
+        let row = terminal
+            .lines()
+            .iter()
+            .position(|line| line.contains(text))
+            .unwrap();
+        let line = &terminal.lines()[row];
+        let column = line[..line.find(text).unwrap()].chars().count() as u16;
+
+        terminal.backend().buffer()[(column, row as u16)].fg
+    };
+
+    // stderr is painted red, stdout keeps the default color
+    assert_eq!(foreground("to stderr"), Color::Red);
+    assert_eq!(foreground("to stdout"), Color::Reset);
+}
+
+#[test]
+fn keys_before_any_event() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    let keys = [
+        (KeyCode::Left, KeyModifiers::NONE),
+        (KeyCode::Left, KeyModifiers::SHIFT),
+        (KeyCode::Char('b'), KeyModifiers::ALT),
+        (KeyCode::Right, KeyModifiers::NONE),
+        (KeyCode::Right, KeyModifiers::SHIFT),
+        (KeyCode::Char('f'), KeyModifiers::ALT),
+        (KeyCode::Up, KeyModifiers::CONTROL),
+        (KeyCode::Char('p'), KeyModifiers::ALT),
+        (KeyCode::Down, KeyModifiers::CONTROL),
+        (KeyCode::Char('n'), KeyModifiers::ALT),
+        (KeyCode::Up, KeyModifiers::NONE),
+        (KeyCode::Up, KeyModifiers::SHIFT),
+        (KeyCode::Up, KeyModifiers::ALT),
+        (KeyCode::Down, KeyModifiers::NONE),
+        (KeyCode::Down, KeyModifiers::SHIFT),
+        (KeyCode::Down, KeyModifiers::ALT),
+        (KeyCode::Char('n'), KeyModifiers::NONE),
+        (KeyCode::Char('N'), KeyModifiers::SHIFT),
+        (KeyCode::Esc, KeyModifiers::NONE),
+        (KeyCode::Char('l'), KeyModifiers::NONE),
+        (KeyCode::Char('+'), KeyModifiers::NONE),
+        (KeyCode::Char('-'), KeyModifiers::NONE),
+        (KeyCode::Char('z'), KeyModifiers::NONE),
+        (KeyCode::Char('z'), KeyModifiers::NONE),
+        (KeyCode::Char('L'), KeyModifiers::SHIFT),
+    ];
+
+    // every binding is ignored until a tab exists
+    for (code, modifiers) in keys {
+        key(&tx, code, modifiers);
+        terminal.step().unwrap();
+    }
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+    });
+
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "INFO api: request"));
+}
+
+#[test]
+fn receiver_source() {
+    let (tx, mut rx) = mpsc::channel();
+
+    // the threaded terminal waits for the next event instead of stopping
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        key(&tx, KeyCode::Char('c'), KeyModifiers::CONTROL);
+    });
+
+    assert!(rx.next_event().is_some());
+    assert!(rx.try_next_event().is_none());
+}
+
+/// Writer collecting the output of a fmt layer
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn headless_layer_on_registry() {
+    let (_tx, rx) = mpsc::channel::<Event>();
+
+    let (layer, mut terminal) = LogTerminal::new(SplitBy::SpanPrefix(SplitFilter::none()))
+        .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+        .into_headless_layer(TestBackend::new(60, 10), rx)
+        .unwrap();
+
+    let buffer = Buffer::default();
+
+    let writer = buffer.clone();
+
+    let subscriber = tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .without_time()
+                .with_ansi(false)
+                .with_writer(move || writer.clone()),
+        )
+        .with(layer);
+
+    tracing::subscriber::with_default(subscriber, || {
+        // the span prefix is looked up in the registry shared by both layers
+        let span = tracing::info_span!("request");
+        let _enter = span.enter();
+
+        tracing::info!(target: "api", "composed");
+    });
+
+    terminal.step().unwrap();
+
+    assert!(terminal.lines()[0].contains("request"));
+    assert!(contains(&terminal, "INFO request: api: composed"));
+    assert!(
+        String::from_utf8_lossy(&buffer.0.lock().unwrap()).contains("INFO request: api: composed")
+    );
+}
+
+#[test]
+fn max_lines_per_instance() {
+    let build = |max_lines| {
+        LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+            .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+            .with_max_lines(max_lines)
+            .finish_headless(TestBackend::new(60, 20), VecDeque::new())
+            .unwrap()
+    };
+
+    let mut small = build(3);
+    let mut large = build(100);
+
+    for terminal in [&mut small, &mut large] {
+        tracing::dispatcher::with_default(terminal.dispatch(), || {
+            for index in 0..10 {
+                tracing::info!(target: "api", "line {index}");
+            }
+        });
+
+        terminal.step().unwrap();
+    }
+
+    assert!(!contains(&small, "line 6"));
+    assert!(contains(&small, "line 7"));
+    assert!(contains(&large, "line 0"));
+}
+
+#[test]
+fn split_by_level() {
+    let (mut terminal, tx) = terminal(
+        SplitBy::Level(SplitFilter::whitelist(vec![
+            "ERROR".to_string(),
+            "WARN".to_string(),
+        ])),
+        60,
+        10,
+    );
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::error!(target: "api", "failed");
+        tracing::warn!(target: "db", "slow");
+        tracing::info!(target: "api", "request");
+    });
+
+    terminal.step().unwrap();
+
+    let lines = terminal.lines();
+
+    assert!(lines[0].contains("ERROR") && lines[0].contains("WARN"));
+    assert!(!lines[0].contains("INFO"));
+    assert!(contains(&terminal, "failed"));
+
+    key(&tx, KeyCode::Right, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "slow"));
+    assert!(!contains(&terminal, "request"));
+}
+
+#[test]
+fn split_by_field() {
+    let (mut terminal, tx) = terminal(SplitBy::Field("service", SplitFilter::none()), 60, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(service = "api", "request");
+        tracing::info!(service = 42, "job");
+        tracing::info!("untagged");
+    });
+
+    terminal.step().unwrap();
+
+    let header = &terminal.lines()[0];
+
+    assert!(header.contains("api") && header.contains("42") && header.contains("undefined"));
+    assert!(contains(&terminal, "request"));
+
+    key(&tx, KeyCode::Right, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "job"));
+}
+
+#[test]
+fn split_custom() {
+    let (mut terminal, _tx) = terminal(
+        SplitBy::custom(|metadata, spans, fields| {
+            if fields.get("skip").is_some() {
+                None
+            } else if metadata.target().starts_with("db::")
+                || spans.names().any(|name| name == "migration")
+            {
+                Some("database".to_string())
+            } else {
+                Some("other".to_string())
+            }
+        }),
+        60,
+        10,
+    );
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "db::pool", "connected");
+
+        tracing::info_span!("migration").in_scope(|| tracing::info!(target: "api", "migrated"));
+
+        tracing::info!(target: "api", skip = true, "hidden");
+        tracing::info!(target: "api", "request");
+    });
+
+    terminal.step().unwrap();
+
+    let header = &terminal.lines()[0];
+
+    assert!(header.contains("database") && header.contains("other"));
+    assert!(contains(&terminal, "connected"));
+    assert!(contains(&terminal, "migrated"));
+    assert!(!contains(&terminal, "hidden"));
+}
+
+#[test]
+fn extra_tabs() {
+    let (tx, rx) = mpsc::channel();
+
+    let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::whitelist(vec![
+        "api".to_string(),
+    ])))
+    .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+    .with_all_tab("all")
+    .with_extra_tab("errors", |metadata| {
+        *metadata.level() == tracing::Level::ERROR
+    })
+    .finish_headless(TestBackend::new(80, 10), rx)
+    .unwrap();
+
+    // the all tab is shown before the first event
+    terminal.step().unwrap();
+
+    assert!(terminal.lines()[0].contains("all"));
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::error!(target: "db", "failed");
+    });
+
+    terminal.step().unwrap();
+
+    assert_eq!(
+        terminal.lines()[0].split_whitespace().collect::<Vec<_>>(),
+        ["all", "│", "api", "│", "errors"]
+    );
+    assert!(contains(&terminal, "request"));
+    assert!(contains(&terminal, "failed"));
+
+    key(&tx, KeyCode::Right, KeyModifiers::NONE);
+    key(&tx, KeyCode::Right, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(!contains(&terminal, "request"));
+    assert!(contains(&terminal, "failed"));
+}
+
+#[test]
+fn split_filter_patterns() {
+    let (mut terminal, _tx) = terminal(
+        SplitBy::Target(SplitFilter::whitelist(vec![
+            Pattern::exact("api"),
+            Pattern::glob("my_crate::net::*").alias("net"),
+            Pattern::regex("^hyper(::|$)").unwrap().alias("http"),
+        ])),
+        80,
+        10,
+    );
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "my_crate::net::tcp", "tcp");
+        tracing::info!(target: "my_crate::net::udp", "udp");
+        tracing::info!(target: "hyper::client", "client");
+        tracing::info!(target: "hyper", "server");
+        tracing::info!(target: "my_crate::db", "query");
+    });
+
+    terminal.step().unwrap();
+
+    assert_eq!(
+        terminal.lines()[0].split_whitespace().collect::<Vec<_>>(),
+        ["api", "│", "net", "│", "http"]
+    );
+}
+
+#[test]
+fn split_filter_aliases() {
+    let (mut terminal, _tx) = terminal(
+        SplitBy::Target(SplitFilter::aliases(vec![
+            Pattern::glob("hyper*").alias("http"),
+        ])),
+        80,
+        10,
+    );
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "hyper::client", "client");
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "hyper_util", "util");
+    });
+
+    terminal.step().unwrap();
+
+    assert_eq!(
+        terminal.lines()[0].split_whitespace().collect::<Vec<_>>(),
+        ["http", "│", "api"]
+    );
+}
+
+#[test]
+fn split_by_span_path() {
+    let emit = |terminal: &Headless<TestBackend>| {
+        tracing::dispatcher::with_default(terminal.dispatch(), || {
+            let _request = tracing::info_span!("request").entered();
+
+            tracing::info!("start");
+
+            let _query = tracing::info_span!("db_query").entered();
+
+            tracing::info_span!("connect").in_scope(|| tracing::info!("connect"));
+        });
+    };
+
+    let (mut root, _tx) = terminal(
+        SplitBy::SpanPath {
+            depth: 2,
+            innermost: false,
+            filter: SplitFilter::none(),
+        },
+        80,
+        10,
+    );
+
+    emit(&root);
+    root.step().unwrap();
+
+    assert_eq!(root.lines()[0].split_whitespace().collect::<Vec<_>>(), [
+        "request",
+        "│",
+        "request/db_query"
+    ]);
+
+    let (mut innermost, _tx) = terminal(
+        SplitBy::SpanPath {
+            depth: 1,
+            innermost: true,
+            filter: SplitFilter::none(),
+        },
+        80,
+        10,
+    );
+
+    emit(&innermost);
+    innermost.step().unwrap();
+
+    assert_eq!(
+        innermost.lines()[0].split_whitespace().collect::<Vec<_>>(),
+        ["request", "│", "connect"]
+    );
+}
+
+#[test]
+fn layouts() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 40, 16);
+
+    let groups = |terminal: &Headless<TestBackend>| {
+        terminal
+            .lines()
+            .iter()
+            .map(|line| line.matches('↑').count())
+            .sum::<usize>()
+    };
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+        tracing::info!(target: "worker", "job");
+    });
+
+    // [api] [db] [worker] in three groups
+    key(&tx, KeyCode::Right, KeyModifiers::SHIFT);
+    key(&tx, KeyCode::Left, KeyModifiers::NONE);
+    key(&tx, KeyCode::Right, KeyModifiers::SHIFT);
+    key(&tx, KeyCode::Right, KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 3);
+    assert!(contains(&terminal, "layout: columns"));
+
+    // rows
+    key(&tx, KeyCode::Char('L'), KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 1);
+    assert_eq!(groups(&terminal), 3);
+    assert!(contains(&terminal, "layout: rows"));
+
+    // grid, two groups on the first row and one on the second
+    key(&tx, KeyCode::Char('L'), KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 2);
+    assert_eq!(groups(&terminal), 3);
+    assert!(contains(&terminal, "layout: grid"));
+
+    // main + stack
+    key(&tx, KeyCode::Char('L'), KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 2);
+    assert_eq!(groups(&terminal), 3);
+    assert!(contains(&terminal, "layout: main + stack"));
+
+    // back to columns, then rows
+    key(&tx, KeyCode::Char('L'), KeyModifiers::SHIFT);
+    key(&tx, KeyCode::Char('L'), KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    // worker is selected in the last row, focus the group above and move its tab up again
+    key(&tx, KeyCode::Char('p'), KeyModifiers::ALT);
+    key(&tx, KeyCode::Up, KeyModifiers::CONTROL);
+    terminal.step().unwrap();
+
+    assert_eq!(groups(&terminal), 2);
+    assert!(terminal.lines()[0].contains("db │ api"));
+}
+
+#[test]
+fn resize_groups() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 40, 10);
+
+    // column of the right border of the first group
+    let border = |terminal: &Headless<TestBackend>| {
+        terminal.lines()[1]
+            .chars()
+            .position(|char| char == '╮')
+            .unwrap()
+    };
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+    });
+
+    // [api] [db], the first group is selected
+    key(&tx, KeyCode::Right, KeyModifiers::SHIFT);
+    key(&tx, KeyCode::Char('b'), KeyModifiers::ALT);
+    terminal.step().unwrap();
+
+    assert_eq!(border(&terminal), 19);
+
+    key(&tx, KeyCode::Char('+'), KeyModifiers::NONE);
+    key(&tx, KeyCode::Char('+'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(border(&terminal) > 19);
+
+    key(&tx, KeyCode::Char('-'), KeyModifiers::NONE);
+    key(&tx, KeyCode::Char('-'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert_eq!(border(&terminal), 19);
+
+    // drag the border between the groups
+    mouse(&tx, MouseEventKind::Down(MouseButton::Left), 19, 5);
+    mouse(&tx, MouseEventKind::Drag(MouseButton::Left), 9, 5);
+    mouse(&tx, MouseEventKind::Up(MouseButton::Left), 9, 5);
+    terminal.step().unwrap();
+
+    assert_eq!(border(&terminal), 9);
+
+    // main + stack, the stack keeps the heights of its rows
+    let (tx, rx) = mpsc::channel();
+
+    let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .with_layout([["api"], ["db"], ["worker"], ["cache"]])
+        .with_layout_mode(LayoutMode::MainStack)
+        .finish_headless(TestBackend::new(40, 25), rx)
+        .unwrap();
+
+    // rows of the top borders of the stack
+    let stack_rows = |terminal: &Headless<TestBackend>| {
+        terminal
+            .lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.ends_with('╮'))
+            .map(|(row, _)| row)
+            .collect::<Vec<_>>()
+    };
+
+    terminal.step().unwrap();
+
+    assert_eq!(border(&terminal), 19);
+
+    let rows = stack_rows(&terminal);
+
+    mouse(&tx, MouseEventKind::Down(MouseButton::Left), 19, 5);
+    mouse(&tx, MouseEventKind::Drag(MouseButton::Left), 27, 5);
+    mouse(&tx, MouseEventKind::Up(MouseButton::Left), 27, 5);
+
+    terminal.step().unwrap();
+
+    assert_eq!(border(&terminal), 27);
+    assert_eq!(stack_rows(&terminal), rows);
+}
+
+#[test]
+fn zoom() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 40, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+    });
+
+    // [db] [api], api is selected
+    key(&tx, KeyCode::Right, KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 2);
+
+    key(&tx, KeyCode::Char('z'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    // only the api content, across the whole frame and without the header of its group
+    assert!(terminal.lines()[0].contains("─ api ─"));
+    assert!(terminal.lines()[0].ends_with('╮'));
+    assert!(contains(&terminal, "INFO api: request"));
+    assert!(!contains(&terminal, "db"));
+
+    key(&tx, KeyCode::Char('z'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 2);
+    assert!(contains(&terminal, "INFO db: query"));
+}
+
+#[test]
+fn zoom_navigation() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 40, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+    });
+
+    // [db] [api], api is selected
+    key(&tx, KeyCode::Right, KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    // the first navigation key only leaves the zoom, the selection is unchanged
+    for (code, modifiers) in [
+        (KeyCode::Left, KeyModifiers::NONE),
+        (KeyCode::Left, KeyModifiers::SHIFT),
+        (KeyCode::Char('b'), KeyModifiers::ALT),
+        (KeyCode::Char('p'), KeyModifiers::ALT),
+    ] {
+        key(&tx, KeyCode::Char('z'), KeyModifiers::NONE);
+        terminal.step().unwrap();
+
+        assert!(!contains(&terminal, "db"));
+
+        key(&tx, code, modifiers);
+        terminal.step().unwrap();
+
+        assert_eq!(terminal.lines()[1].matches('╮').count(), 2);
+        assert!(contains(&terminal, "INFO db: query"));
+        assert!(terminal.lines()[0].contains("api"));
+    }
+
+    // once the groups are shown again the keys move between them
+    key(&tx, KeyCode::Char('b'), KeyModifiers::ALT);
+    key(&tx, KeyCode::Char('z'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "INFO db: query"));
+    assert!(!contains(&terminal, "api"));
+}
+
+#[test]
+fn layout_file() {
+    let path = std::env::temp_dir().join(format!("log-terminal-layout-{}", std::process::id()));
+
+    std::fs::write(&path, "group 100\n  db\n* api\ngroup 150\n* worker\n").unwrap();
+
+    let (tx, rx) = mpsc::channel();
+
+    let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+        .with_layout_file(&path)
+        .finish_headless(TestBackend::new(40, 10), rx)
+        .unwrap();
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "worker", "job");
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+    });
+
+    terminal.step().unwrap();
+
+    // [db api] [worker], worker is wider and api is open in its group
+    assert!(terminal.lines()[0].contains("db │ api"));
+    assert!(terminal.lines()[0].contains("worker"));
+    assert_eq!(
+        terminal.lines()[1].chars().position(|char| char == '╮'),
+        Some(15)
+    );
+    assert!(contains(&terminal, "request"));
+    assert!(!contains(&terminal, "query"));
+
+    let saved = || std::fs::read_to_string(&path).unwrap();
+
+    // move worker in the first group
+    key(&tx, KeyCode::Left, KeyModifiers::SHIFT);
+    terminal.step().unwrap();
+
+    assert_eq!(saved(), "group 100\n  db\n  api\n* worker\n");
+
+    // a new selection is not written until the exit
+    key(&tx, KeyCode::Left, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert_eq!(saved(), "group 100\n  db\n  api\n* worker\n");
+
+    key(&tx, KeyCode::Char('c'), KeyModifiers::CONTROL);
+    terminal.step().unwrap();
+
+    assert_eq!(saved(), "group 100\n  db\n* api\n  worker\n");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn declared_layout() {
+    let (_tx, rx) = mpsc::channel();
+
+    let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+        .with_layout([vec!["api", "worker"], vec!["db"], vec!["errors"]])
+        .finish_headless(TestBackend::new(90, 10), rx)
+        .unwrap();
+
+    terminal.step().unwrap();
+
+    // shown before any event
+    assert!(terminal.lines()[0].contains("api │ worker"));
+    assert!(terminal.lines()[0].contains("db"));
+    assert!(terminal.lines()[0].contains("errors"));
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 3);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "cache", "hit");
+        tracing::info!(target: "worker", "job");
+    });
+
+    terminal.step().unwrap();
+
+    // undeclared tabs join the first group
+    assert!(terminal.lines()[0].contains("api │ worker │ cache"));
+    assert_eq!(terminal.lines()[1].matches('╮').count(), 3);
+}
+
+#[test]
+fn set_max_level() {
+    let (_tx, rx) = mpsc::channel::<Event>();
+
+    let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+        .with_max_level(tracing::Level::INFO)
+        .finish_headless(TestBackend::new(60, 10), rx)
+        .unwrap();
+
+    // the same callsites are hit before and after the change
+    let log = |index| {
+        tracing::info!(target: "api", "info {index}");
+        tracing::debug!(target: "api", "debug {index}");
+    };
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        log(0);
+
+        // inside the scope, the interest of the callsites is cached again for this dispatcher
+        terminal
+            .handle()
+            .set_max_level(tracing::Level::DEBUG)
+            .unwrap();
+
+        log(1);
+    });
+
+    assert_eq!(terminal.handle().max_level(), tracing::Level::DEBUG);
+
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "INFO api: info 0"));
+    assert!(!contains(&terminal, "debug 0"));
+    assert!(contains(&terminal, "INFO api: info 1"));
+    assert!(contains(&terminal, "DEBUG api: debug 1"));
+}
+
+/// Background of the first cell of `text` in the row `row`
+fn background(terminal: &Headless<TestBackend>, row: u16, text: &str) -> Color {
+    let line = &terminal.lines()[row as usize];
+
+    let column = line[..line.find(text).unwrap()].chars().count() as u16;
+
+    terminal.backend().buffer()[(column, row)].bg
+}
+
+fn type_text(tx: &mpsc::Sender<Event>, text: &str) {
+    for char in text.chars() {
+        key(tx, KeyCode::Char(char), KeyModifiers::NONE);
+    }
+}
+
+#[test]
+fn search() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "alpha one");
+        tracing::info!(target: "api", "beta two");
+        tracing::info!(target: "api", "alpha three");
+    });
+
+    key(&tx, KeyCode::Char('/'), KeyModifiers::NONE);
+    type_text(&tx, "alpha");
+    key(&tx, KeyCode::Enter, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "/alpha [2 matches]"));
+
+    // the closest match above the position is the current one
+    assert_eq!(background(&terminal, 2, "alpha"), Color::Yellow);
+    assert_eq!(background(&terminal, 3, "beta"), Color::Reset);
+    assert_eq!(background(&terminal, 4, "alpha"), Color::LightRed);
+
+    // the next match wraps to the first one
+    key(&tx, KeyCode::Char('n'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert_eq!(background(&terminal, 2, "alpha"), Color::LightRed);
+    assert_eq!(background(&terminal, 4, "alpha"), Color::Yellow);
+
+    key(&tx, KeyCode::Esc, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(!contains(&terminal, "matches]"));
+    assert_eq!(background(&terminal, 2, "alpha"), Color::Reset);
+}
+
+#[test]
+fn search_wrapped_match() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 20, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "aaaaaaaa needle");
+    });
+
+    key(&tx, KeyCode::Char('/'), KeyModifiers::NONE);
+    type_text(&tx, ": aaaa");
+    key(&tx, KeyCode::Enter, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    // the row matches but the match is split by the wrap, so it is not highlighted
+    assert!(contains(&terminal, "[1 matches]"));
+    assert!(
+        terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .all(|cell| cell.bg != Color::LightRed)
+    );
+}
+
+#[test]
+fn search_prompt_follows_selection() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 20);
+
+    // without tabs the search does not start, the bindings keep working
+    key(&tx, KeyCode::Char('/'), KeyModifiers::NONE);
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, " Help "));
+
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "request");
+        tracing::info!(target: "db", "query");
+    });
+
+    key(&tx, KeyCode::Char('/'), KeyModifiers::NONE);
+    type_text(&tx, "req");
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "/req_"));
+
+    // selecting another tab closes the prompt
+    let column = terminal.lines()[0].find("db").unwrap() as u16;
+
+    mouse(&tx, MouseEventKind::Down(MouseButton::Left), column, 0);
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, " Help "));
+
+    // the search of api is kept as if confirmed
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+    mouse(&tx, MouseEventKind::Down(MouseButton::Left), 2, 0);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "/req [1 matches]"));
+}
+
+#[test]
+fn filter() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    let bottom = |terminal: &Headless<TestBackend>| terminal.lines()[8].clone();
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "GET /users");
+        tracing::info!(target: "api", "POST /orders");
+        tracing::info!(target: "api", "GET /orders");
+    });
+
+    // substring
+    key(&tx, KeyCode::Char('&'), KeyModifiers::NONE);
+    type_text(&tx, "GET");
+    key(&tx, KeyCode::Enter, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "GET /users"));
+    assert!(contains(&terminal, "GET /orders"));
+    assert!(!contains(&terminal, "POST /orders"));
+    assert!(bottom(&terminal).contains("Filter: GET"));
+
+    // the new events are filtered too
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "GET /items");
+        tracing::info!(target: "api", "POST /items");
+    });
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "GET /items"));
+    assert!(!contains(&terminal, "POST /items"));
+
+    // regex
+    key(&tx, KeyCode::Char('&'), KeyModifiers::NONE);
+    key(&tx, KeyCode::Tab, KeyModifiers::NONE);
+    type_text(&tx, "POST|users");
+    key(&tx, KeyCode::Enter, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "GET /users"));
+    assert!(contains(&terminal, "POST /orders"));
+    assert!(contains(&terminal, "POST /items"));
+    assert!(!contains(&terminal, "GET /orders"));
+    assert!(bottom(&terminal).contains("Filter (regex): POST|users"));
+
+    // cleared with Esc
+    key(&tx, KeyCode::Esc, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "GET /orders"));
+    assert!(contains(&terminal, "POST /items"));
+    assert!(!bottom(&terminal).contains("Filter"));
+}
+
+#[test]
+fn filter_prompt_follows_selection() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 20);
+
+    // without tabs the filter does not start, the bindings keep working
+    key(&tx, KeyCode::Char('&'), KeyModifiers::NONE);
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, " Help "));
+
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::info!(target: "api", "GET /users");
+        tracing::info!(target: "api", "POST /orders");
+        tracing::info!(target: "db", "query");
+    });
+
+    key(&tx, KeyCode::Char('&'), KeyModifiers::NONE);
+    type_text(&tx, "GET");
+    terminal.step().unwrap();
+
+    assert!(!contains(&terminal, "POST /orders"));
+
+    // selecting another tab closes the prompt
+    let column = terminal.lines()[0].find("db").unwrap() as u16;
+
+    mouse(&tx, MouseEventKind::Down(MouseButton::Left), column, 0);
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, " Help "));
+
+    // the filter of api is kept as if confirmed
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+    mouse(&tx, MouseEventKind::Down(MouseButton::Left), 2, 0);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "Filter: GET"));
+    assert!(contains(&terminal, "GET /users"));
+    assert!(!contains(&terminal, "POST /orders"));
+}
+
+#[test]
+fn filter_title_narrow_pane() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 36, 6);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        for index in 0..10 {
+            tracing::info!(target: "api", "GET /users/{index}");
+        }
+    });
+
+    // scrolling, level and filter titles together
+    key(&tx, KeyCode::Up, KeyModifiers::NONE);
+    key(&tx, KeyCode::Char('l'), KeyModifiers::NONE);
+    key(&tx, KeyCode::Char('&'), KeyModifiers::NONE);
+    type_text(&tx, "users");
+    key(&tx, KeyCode::Enter, KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    // cut at the border of the pane
+    assert!(terminal.lines()[4].contains(" Scrolling: 1 │ Level: DEBUG │ Fi…╯"));
+
+    terminal.backend_mut().resize(60, 6);
+    terminal.step().unwrap();
+
+    assert!(terminal.lines()[4].contains(" Scrolling: 1 │ Level: DEBUG │ Filter: users "));
+}
+
+#[test]
+fn cycle_level() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        tracing::debug!(target: "api", "details");
+        tracing::info!(target: "api", "request");
+        tracing::warn!(target: "api", "slow");
+    });
+
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "details"));
+    assert!(!terminal.lines()[8].contains("Level"));
+
+    // trace -> debug -> info
+    key(&tx, KeyCode::Char('l'), KeyModifiers::NONE);
+    key(&tx, KeyCode::Char('l'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(!contains(&terminal, "details"));
+    assert!(contains(&terminal, "request"));
+    assert!(terminal.lines()[8].contains("Level: INFO"));
+
+    key(&tx, KeyCode::Char('l'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(!contains(&terminal, "request"));
+    assert!(contains(&terminal, "slow"));
+    assert!(terminal.lines()[8].contains("Level: WARN"));
+
+    // error -> back to trace
+    key(&tx, KeyCode::Char('l'), KeyModifiers::NONE);
+    key(&tx, KeyCode::Char('l'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "details"));
+    assert!(!terminal.lines()[8].contains("Level"));
+}
+
+#[test]
+fn mouse_click_and_wheel() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 60, 10);
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        for index in 0..20 {
+            tracing::info!(target: "api", "request {index}");
+        }
+        tracing::info!(target: "db", "query");
+    });
+
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "request 19"));
+
+    // click the title of db in the header
+    let column = terminal.lines()[0].find("db").unwrap() as u16;
+
+    mouse(&tx, MouseEventKind::Down(MouseButton::Left), column, 0);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "INFO db: query"));
+    assert!(!contains(&terminal, "request 19"));
+
+    // back to api, then scroll it with the wheel
+    mouse(&tx, MouseEventKind::Down(MouseButton::Left), 2, 0);
+    mouse(&tx, MouseEventKind::ScrollUp, 10, 5);
+    terminal.step().unwrap();
+
+    assert!(!contains(&terminal, "request 19"));
+    assert!(contains(&terminal, "Scrolling: 3"));
+
+    mouse(&tx, MouseEventKind::ScrollDown, 10, 5);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "request 19"));
+    assert!(!contains(&terminal, "Scrolling"));
+}
+
+#[test]
+fn help() {
+    let (mut terminal, tx) = terminal(SplitBy::Target(SplitFilter::none()), 80, 40);
+
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, "?: help"));
+    assert!(!contains(&terminal, " Help "));
+
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(contains(&terminal, " Help "));
+    assert!(contains(&terminal, "Search"));
+    assert!(contains(&terminal, "Switch the layout of the groups"));
+    // the mouse capture is disabled
+    assert!(!contains(&terminal, "Wheel"));
+
+    key(&tx, KeyCode::Char('?'), KeyModifiers::NONE);
+    terminal.step().unwrap();
+
+    assert!(!contains(&terminal, " Help "));
+}
+
+/// Set in the child process of `panic_report`
+const PANIC_CHILD: &str = "LOG_TERMINAL_PANIC_CHILD";
+
+#[test]
+#[ignore = "run by panic_report in a child process"]
+fn panic_report_child() {
+    if std::env::var_os(PANIC_CHILD).is_none() {
+        return;
+    }
+
+    let (_tx, rx) = mpsc::channel::<Event>();
+
+    let mut terminal = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .customize_fmt_layer(|layer| layer.without_time().with_ansi(false))
+        .with_panic_lines(2)
+        .finish_headless(TestBackend::new(60, 10), rx)
+        .unwrap();
+
+    terminal.set_panic_hook();
+
+    tracing::dispatcher::with_default(terminal.dispatch(), || {
+        for index in 0..5 {
+            tracing::info!(target: "api", "request {index}");
+        }
+    });
+
+    terminal.step().unwrap();
+
+    panic!("boom");
+}
+
+#[test]
+fn panic_report() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "panic_report_child", "--ignored", "--nocapture"])
+        .env(PANIC_CHILD, "1")
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success());
+
+    // the terminal is restored, leaving the alternate screen
+    assert!(stdout.contains("\x1b[?1049l"));
+
+    // the last rows follow the panic message
+    let panic = stderr.find("boom").unwrap();
+    let report = stderr.find("----- api -----").unwrap();
+
+    assert!(panic < report);
+    assert!(stderr[report..].contains("INFO api: request 3"));
+    assert!(stderr[report..].contains("INFO api: request 4"));
+    assert!(!stderr[report..].contains("request 2"));
+}
@@ -4,6 +4,7 @@ use {
 };
 
 #[test]
+#[ignore = "needs a TTY and never returns"]
 fn inputs() {
     let mut terminal = ratatui::init();
 
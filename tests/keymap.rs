@@ -0,0 +1,95 @@
+use {
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    log_terminal::{KeyAction, KeyMap},
+};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn rebind_action() {
+    let keymap = KeyMap::default()
+        .unbind_action(KeyAction::Help)
+        .bind(key(KeyCode::F(1), KeyModifiers::NONE), KeyAction::Help);
+
+    assert_eq!(
+        keymap.action(&key(KeyCode::F(1), KeyModifiers::NONE)),
+        Some(KeyAction::Help)
+    );
+    assert_eq!(
+        keymap.action(&key(KeyCode::Char('?'), KeyModifiers::NONE)),
+        None
+    );
+    assert_eq!(keymap.keys_of(KeyAction::Help), vec!["F1".to_string()]);
+}
+
+#[test]
+fn bind_replaces_the_key() {
+    let keymap = KeyMap::default().bind(
+        key(KeyCode::Char('/'), KeyModifiers::NONE),
+        KeyAction::Filter,
+    );
+
+    assert_eq!(
+        keymap.action(&key(KeyCode::Char('/'), KeyModifiers::NONE)),
+        Some(KeyAction::Filter)
+    );
+    assert!(keymap.keys_of(KeyAction::Search).is_empty());
+}
+
+#[test]
+fn unbind_default() {
+    let keymap = KeyMap::default().unbind(key(KeyCode::Char('l'), KeyModifiers::NONE));
+
+    assert_eq!(
+        keymap.action(&key(KeyCode::Char('l'), KeyModifiers::NONE)),
+        None
+    );
+    // the other bindings are kept
+    assert_eq!(
+        keymap.action(&key(KeyCode::Char('L'), KeyModifiers::SHIFT)),
+        Some(KeyAction::CycleLayout)
+    );
+}
+
+#[test]
+fn symbols_ignore_shift() {
+    let keymap = KeyMap::default();
+
+    // symbols are reported with or without SHIFT depending on the terminal
+    for modifiers in [KeyModifiers::NONE, KeyModifiers::SHIFT] {
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('?'), modifiers)),
+            Some(KeyAction::Help)
+        );
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('+'), modifiers)),
+            Some(KeyAction::Grow)
+        );
+    }
+
+    // other modifiers still count
+    assert_eq!(
+        keymap.action(&key(KeyCode::Char('?'), KeyModifiers::CONTROL)),
+        None
+    );
+
+    // letters keep SHIFT apart
+    assert_eq!(
+        keymap.action(&key(KeyCode::Char('n'), KeyModifiers::NONE)),
+        Some(KeyAction::SearchNext)
+    );
+    assert_eq!(
+        keymap.action(&key(KeyCode::Char('N'), KeyModifiers::SHIFT)),
+        Some(KeyAction::SearchPrevious)
+    );
+
+    // unbinding a symbol removes it whatever the SHIFT
+    let keymap = keymap.unbind(key(KeyCode::Char('?'), KeyModifiers::SHIFT));
+
+    assert_eq!(
+        keymap.action(&key(KeyCode::Char('?'), KeyModifiers::NONE)),
+        None
+    );
+}
@@ -1,6 +1,134 @@
-use log_terminal::{LogTerminal, SplitBy, SplitFilter};
+use {
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    log_terminal::{ExitMode, LogTerminal, SplitBy, SplitFilter},
+    std::{
+        io,
+        sync::{Arc, Mutex, mpsc},
+    },
+    tracing_subscriber::layer::SubscriberExt,
+};
+
+/// Writer collecting the output of a fmt layer
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    fn contains(&self, text: &str) -> bool {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).contains(text)
+    }
+}
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Source closing the terminal once `exit` is called
+fn exit_source() -> (mpsc::Receiver<Event>, impl FnOnce()) {
+    let (tx, rx) = mpsc::channel();
+
+    let exit = move || {
+        tx.send(Event::Key(KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL,
+        )))
+        .unwrap();
+    };
+
+    (rx, exit)
+}
+
+#[test]
+#[ignore = "needs a TTY"]
+fn finish_subscriber_with_layers() {
+    let (source, exit) = exit_source();
+
+    let (subscriber, handle) = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .with_exit_mode(ExitMode::Stderr)
+        .with_event_source(source)
+        .finish_subscriber();
+
+    let buffer = Buffer::default();
+
+    let writer = buffer.clone();
+
+    let subscriber = subscriber.with(
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(move || writer.clone()),
+    );
+
+    tracing::subscriber::with_default(subscriber, || tracing::info!("stacked"));
+
+    exit();
+    handle.wait_shutdown();
+
+    assert!(buffer.contains("stacked"));
+}
+
+#[test]
+#[ignore = "needs a TTY"]
+fn into_layer_on_registry() {
+    let (source, exit) = exit_source();
+
+    let (terminal, handle) = LogTerminal::new(SplitBy::SpanPrefix(SplitFilter::none()))
+        .with_exit_mode(ExitMode::Stderr)
+        .with_event_source(source)
+        .into_layer();
+
+    let buffer = Buffer::default();
+
+    let writer = buffer.clone();
+
+    let subscriber = tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || writer.clone()),
+        )
+        .with(terminal);
+
+    tracing::subscriber::with_default(subscriber, || {
+        // the span prefix is looked up in the registry shared by both layers
+        let span = tracing::info_span!("request");
+        let _enter = span.enter();
+
+        tracing::info!("composed");
+    });
+
+    exit();
+    handle.wait_shutdown();
+
+    assert!(buffer.contains("request: works: composed"));
+}
+
+#[test]
+#[ignore = "needs a TTY"]
+fn finish_twice() {
+    let (source, exit) = exit_source();
+
+    let handle = LogTerminal::new(SplitBy::Target(SplitFilter::none()))
+        .with_exit_mode(ExitMode::Stderr)
+        .with_event_source(source)
+        .finish();
+
+    // the second terminal is not started, its handle is the one of the first
+    let second = LogTerminal::new(SplitBy::Target(SplitFilter::none())).finish();
+
+    exit();
+    handle.wait_shutdown();
+
+    assert!(second.is_shutdown());
+}
 
 #[test]
+#[ignore = "needs a TTY and never returns"]
 fn span_prefix() {
     LogTerminal::new(SplitBy::SpanPrefix(SplitFilter::none()))
         .with_max_level(tracing::Level::INFO)
@@ -22,6 +150,7 @@ fn span_prefix() {
 }
 
 #[test]
+#[ignore = "needs a TTY and never returns"]
 fn max_lines() {
     LogTerminal::new(SplitBy::SpanPrefix(SplitFilter::none()))
         .with_max_lines(30)